@@ -11,7 +11,7 @@ macro_rules! structs {
     };
 }
 
-structs!(MonoDomain MonoAssemblyName MonoAssembly MonoImage MonoClass MonoClassField MonoMethod MonoObject MonoString MonoMethodDesc MonoProperty);
+structs!(MonoDomain MonoAssemblyName MonoAssembly MonoImage MonoClass MonoClassField MonoMethod MonoObject MonoString MonoMethodDesc MonoProperty MonoArray);
 
 type mono_bool = i32; // int32_t
 
@@ -58,6 +58,20 @@ extern "C" {
         exc: *mut *mut MonoObject,
     ) -> *mut MonoObject;
     fn mono_object_unbox(obj: *mut MonoObject) -> *mut c_void;
+    fn mono_object_get_class(obj: *mut MonoObject) -> *mut MonoClass;
+    fn mono_class_get_name(klass: *mut MonoClass) -> *const c_char;
+    fn mono_class_get_namespace(klass: *mut MonoClass) -> *const c_char;
+    fn mono_class_get_element_class(klass: *mut MonoClass) -> *mut MonoClass;
+    fn mono_class_is_valuetype(klass: *mut MonoClass) -> mono_bool;
+    fn mono_class_array_element_size(klass: *mut MonoClass) -> i32;
+    fn mono_method_get_flags(method: *mut MonoMethod, iflags: *mut u32) -> u32;
+    fn mono_array_length(array: *mut MonoArray) -> usize;
+    fn mono_array_addr_with_size(array: *mut MonoArray, size: c_int, idx: usize) -> *mut c_char;
+    fn mono_value_box(
+        domain: *mut MonoDomain,
+        klass: *mut MonoClass,
+        val: *mut c_void,
+    ) -> *mut MonoObject;
 }
 
 #[no_mangle]
@@ -71,16 +85,63 @@ struct String {
 
 struct Entry {
   String message;
+  i32 mode;
 }
 
 struct Result {
   u64 byte_length; // excluding this field
   i32 version; // ensure data is not corrupt
+  String unity_version;
+  String os_description;
+  String build_target;
+  String current_directory;
+  i32 editor_state; // EditorState* flags
+  i32 enter_play_mode_options; // EnterPlayModeOptions, 0 if disabled, -1 if not supported
+  String selection_type; // the full name of the type of Selection.activeObject, empty if none
+  i32 scene_count;
+  String scene_paths[scene_count]; // open scenes
+  i32 console_cleared; // 1 if the entry at the cursor is not found
+  i32 cursor_count; // the number of entries in the console
+  u64 cursor_fingerprint; // the fingerprint of the last entry in the console
   i32 length;
-  Entry entries[length];
+  Entry entries[length]; // entries after the cursor
+  i32 compiler_message_count; // -1 if not supported
+  CompilerMessage compiler_messages[compiler_message_count];
+}
+
+struct CompilerMessage {
+  String assembly; // empty if not known
+  String file;
+  i32 line;
+  i32 column;
+  i32 type; // CompilerMessageType
+  String message;
 }
  */
 
+/// The options passed from ConsoleLogSaver. Null pointer is passed for default options.
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+struct SaveOptions {
+    /// The number of entries in the console at the previous capture. 0 to transfer all entries.
+    cursor_count: i32,
+    _reserved: i32,
+    /// The fingerprint of the entry at `cursor_count - 1`
+    cursor_fingerprint: u64,
+}
+
+/// FNV-1a hash of the message and mode
+fn fingerprint(message: &[u16], mode: i32) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+    let mode = mode.to_le_bytes();
+    let bytes = bytemuck::cast_slice::<u16, u8>(message).iter().chain(&mode);
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
 macro_rules! cs {
     ($string: literal) => {
         concat!($string, "\0").as_ptr() as *const c_char
@@ -103,6 +164,10 @@ impl TransferDataBuilder {
         self.builder.extend_from_slice(&value.to_ne_bytes());
     }
 
+    fn write_u64(&mut self, value: u64) {
+        self.builder.extend_from_slice(&value.to_ne_bytes());
+    }
+
     fn write_string(&mut self, chars_slicee: &[u16]) {
         self.write_i32(chars_slicee.len() as i32);
         self.builder
@@ -139,9 +204,18 @@ const LogLevelLog: i32 = 1 << 7;
 const LogLevelWarning: i32 = 1 << 8;
 const LogLevelError: i32 = 1 << 9;
 
+const METHOD_ATTRIBUTE_STATIC: u32 = 0x0010;
+
+const EditorStatePlaying: i32 = 1 << 0;
+const EditorStatePaused: i32 = 1 << 1;
+const EditorStateWillChangePlaymode: i32 = 1 << 2;
+const EditorStateCompiling: i32 = 1 << 3;
+const EditorStateUpdating: i32 = 1 << 4;
+
 #[no_mangle]
-extern "C" fn CONSOLE_LOG_SAVER_SAVE() {
+extern "C" fn CONSOLE_LOG_SAVER_SAVE(options: *const SaveOptions) {
     unsafe {
+        let options = options.as_ref().copied().unwrap_or_default();
         let domain = mono_domain_get();
 
         unsafe fn get_assembly(name: *const c_char) -> *mut MonoImage {
@@ -224,8 +298,37 @@ extern "C" fn CONSOLE_LOG_SAVER_SAVE() {
             }
         }
 
+        // the properties below may not exist in some versions of unity so null is checked
+        unsafe fn get_static_property(
+            class: *mut MonoClass,
+            name: *const c_char,
+        ) -> *mut MonoObject {
+            unsafe {
+                if class.is_null() {
+                    return null_mut();
+                }
+                let property = mono_class_get_property_from_name(class, name);
+                if property.is_null() {
+                    return null_mut();
+                }
+                let getter = mono_property_get_get_method(property);
+                if getter.is_null() {
+                    return null_mut();
+                }
+                mono_runtime_invoke(getter, null_mut(), null_mut(), null_mut())
+            }
+        }
+
+        unsafe fn unbox_i32(obj: *mut MonoObject) -> Option<i32> {
+            unsafe { (!obj.is_null()).then(|| *(mono_object_unbox(obj) as *const i32)) }
+        }
+
+        unsafe fn unbox_bool(obj: *mut MonoObject) -> Option<bool> {
+            unsafe { (!obj.is_null()).then(|| *(mono_object_unbox(obj) as *const u8) != 0) }
+        }
+
         let mut data_builder = TransferDataBuilder::new();
-        data_builder.write_i32(1i32);
+        data_builder.write_i32(4i32);
 
         // general info
         let unityVersion = mono_runtime_invoke(
@@ -261,6 +364,105 @@ extern "C" fn CONSOLE_LOG_SAVER_SAVE() {
         );
         data_builder.write_string(mono_string_to_slice(current_directory as *mut _));
 
+        // editor state
+        let EditorApplication =
+            mono_class_from_name(unity_editor, cs!("UnityEditor"), cs!("EditorApplication"));
+        let mut editor_state = 0;
+        for (name, flag) in [
+            (cs!("isPlaying"), EditorStatePlaying),
+            (cs!("isPaused"), EditorStatePaused),
+            (
+                cs!("isPlayingOrWillChangePlaymode"),
+                EditorStateWillChangePlaymode,
+            ),
+            (cs!("isCompiling"), EditorStateCompiling),
+            (cs!("isUpdating"), EditorStateUpdating),
+        ] {
+            if unbox_bool(get_static_property(EditorApplication, name)) == Some(true) {
+                editor_state |= flag;
+            }
+        }
+        data_builder.write_i32(editor_state);
+
+        let EditorSettings =
+            mono_class_from_name(unity_editor, cs!("UnityEditor"), cs!("EditorSettings"));
+        let enter_play_mode_options_enabled = unbox_bool(get_static_property(
+            EditorSettings,
+            cs!("enterPlayModeOptionsEnabled"),
+        ));
+        let enter_play_mode_options = unbox_i32(get_static_property(
+            EditorSettings,
+            cs!("enterPlayModeOptions"),
+        ));
+        let enter_play_mode_options =
+            match (enter_play_mode_options_enabled, enter_play_mode_options) {
+                (Some(false), Some(_)) => 0,
+                (_, Some(options)) => options,
+                (_, None) => -1,
+            };
+        data_builder.write_i32(enter_play_mode_options);
+
+        let Selection = mono_class_from_name(unity_editor, cs!("UnityEditor"), cs!("Selection"));
+        let active_object = get_static_property(Selection, cs!("activeObject"));
+        let selection_type = if active_object.is_null() {
+            String::new()
+        } else {
+            let class = mono_object_get_class(active_object);
+            let namespace = std::ffi::CStr::from_ptr(mono_class_get_namespace(class));
+            let name = std::ffi::CStr::from_ptr(mono_class_get_name(class));
+            if namespace.is_empty() {
+                name.to_string_lossy().into_owned()
+            } else {
+                format!("{}.{}", namespace.to_string_lossy(), name.to_string_lossy())
+            }
+        };
+        data_builder.write_string(&selection_type.encode_utf16().collect::<Vec<_>>());
+
+        let SceneManager = mono_class_from_name(
+            unity_engine,
+            cs!("UnityEngine.SceneManagement"),
+            cs!("SceneManager"),
+        );
+        let SceneClass = mono_class_from_name(
+            unity_engine,
+            cs!("UnityEngine.SceneManagement"),
+            cs!("Scene"),
+        );
+        let GetSceneAt = if SceneManager.is_null() {
+            null_mut()
+        } else {
+            mono_method_desc_search_in_class(
+                mono_method_desc_new(cs!(":GetSceneAt(int)"), 1),
+                SceneManager,
+            )
+        };
+        let Scene_path = if SceneClass.is_null() {
+            null_mut()
+        } else {
+            mono_class_get_property_from_name(SceneClass, cs!("path"))
+        };
+        let scene_count = unbox_i32(get_static_property(SceneManager, cs!("sceneCount")));
+        match scene_count {
+            Some(scene_count) if !GetSceneAt.is_null() && !Scene_path.is_null() => {
+                let Scene_path_get = mono_property_get_get_method(Scene_path);
+                data_builder.write_i32(scene_count);
+                for mut index in 0..scene_count {
+                    let args: &mut [*mut c_void] = &mut [&mut index as *mut _ as *mut _];
+                    let scene =
+                        mono_runtime_invoke(GetSceneAt, null_mut(), args.as_mut_ptr(), null_mut());
+                    // Scene is a struct so the pointer to the value is passed as this
+                    let path = mono_runtime_invoke(
+                        Scene_path_get,
+                        mono_object_unbox(scene) as *mut _,
+                        null_mut(),
+                        null_mut(),
+                    );
+                    data_builder.write_string(mono_string_to_slice(path as *mut _));
+                }
+            }
+            _ => data_builder.write_i32(0),
+        }
+
         // log info
 
         // first, we get flags
@@ -324,10 +526,7 @@ extern "C" fn CONSOLE_LOG_SAVER_SAVE() {
         // void *$message_obj;
         // int $line, $mode;
 
-        // array size will be set to this place later
-        data_builder.write_i32(count);
-
-        for mut index in 0..count {
+        let get_entry = |mut index: i32| {
             let mut message_obj: *mut MonoString = null_mut();
             let mut line: i32 = 0;
             let mut mode: i32 = 0;
@@ -341,13 +540,122 @@ extern "C" fn CONSOLE_LOG_SAVER_SAVE() {
             );
             mono_field_get_value(logentry, LogEntryClass_line, &mut line as *mut _ as *mut _);
             mono_field_get_value(logentry, LogEntryClass_mode, &mut mode as *mut _ as *mut _);
+            (mono_string_to_slice(message_obj), mode)
+        };
+
+        // find the first entry after the cursor
+        let mut start = 0;
+        let mut console_cleared = false;
+        if options.cursor_count > 0 {
+            let cursor_index = options.cursor_count - 1;
+            if cursor_index < count && {
+                let (message, mode) = get_entry(cursor_index);
+                fingerprint(message, mode) == options.cursor_fingerprint
+            } {
+                start = options.cursor_count;
+            } else {
+                console_cleared = true;
+            }
+        }
+
+        let cursor_fingerprint = if count > 0 {
+            let (message, mode) = get_entry(count - 1);
+            fingerprint(message, mode)
+        } else {
+            0
+        };
+
+        data_builder.write_i32(console_cleared as i32);
+        data_builder.write_i32(count);
+        data_builder.write_u64(cursor_fingerprint);
+
+        data_builder.write_i32(count - start);
 
-            data_builder.write_string(mono_string_to_slice(message_obj));
+        for index in start..count {
+            let (message, mode) = get_entry(index);
+            data_builder.write_string(message);
             data_builder.write_i32(mode);
         }
 
         mono_runtime_invoke(EndGettingEntries, null_mut(), null_mut(), null_mut());
 
+        // compiler messages of the last compilation, with internal API
+        let EditorCompilationInterface = mono_class_from_name(
+            unity_editor,
+            cs!("UnityEditor.Scripting.ScriptCompilation"),
+            cs!("EditorCompilationInterface"),
+        );
+        let GetCompileMessages = if EditorCompilationInterface.is_null() {
+            null_mut()
+        } else {
+            mono_method_desc_search_in_class(
+                mono_method_desc_new(cs!(":GetCompileMessages()"), 1),
+                EditorCompilationInterface,
+            )
+        };
+        let is_static = !GetCompileMessages.is_null()
+            && mono_method_get_flags(GetCompileMessages, null_mut()) & METHOD_ATTRIBUTE_STATIC != 0;
+        let compiler_messages = if is_static {
+            mono_runtime_invoke(GetCompileMessages, null_mut(), null_mut(), null_mut())
+                as *mut MonoArray
+        } else {
+            null_mut()
+        };
+
+        if compiler_messages.is_null() {
+            data_builder.write_i32(-1);
+        } else {
+            let length = mono_array_length(compiler_messages);
+            let element_class =
+                mono_class_get_element_class(mono_object_get_class(compiler_messages as *mut _));
+            let is_valuetype = mono_class_is_valuetype(element_class) != 0;
+            let element_size = mono_class_array_element_size(element_class);
+
+            let field = |name| mono_class_get_field_from_name(element_class, name);
+            let (message_field, file_field, line_field, column_field, type_field, assembly_field) = (
+                field(cs!("message")),
+                field(cs!("file")),
+                field(cs!("line")),
+                field(cs!("column")),
+                field(cs!("type")),
+                field(cs!("assemblyName")),
+            );
+            let get_string = |obj, field: *mut MonoClassField| -> &[u16] {
+                let mut value: *mut MonoString = null_mut();
+                if !field.is_null() {
+                    mono_field_get_value(obj, field, &mut value as *mut _ as *mut _);
+                }
+                if value.is_null() {
+                    &[]
+                } else {
+                    mono_string_to_slice(value)
+                }
+            };
+            let get_i32 = |obj, field: *mut MonoClassField| -> i32 {
+                let mut value = 0;
+                if !field.is_null() {
+                    mono_field_get_value(obj, field, &mut value as *mut _ as *mut _);
+                }
+                value
+            };
+
+            data_builder.write_i32(length as i32);
+            for index in 0..length {
+                let address = mono_array_addr_with_size(compiler_messages, element_size, index);
+                let message = if is_valuetype {
+                    mono_value_box(domain, element_class, address as *mut _)
+                } else {
+                    *(address as *mut *mut MonoObject)
+                };
+                data_builder.write_string(get_string(message, assembly_field));
+                data_builder.write_string(get_string(message, file_field));
+                data_builder.write_i32(get_i32(message, line_field));
+                data_builder.write_i32(get_i32(message, column_field));
+                data_builder.write_i32(get_i32(message, type_field));
+                data_builder.write_string(get_string(message, message_field));
+            }
+        }
+
         // restore console flags
         mono_runtime_invoke(
             LogEntries_consoleFlags_set,
@@ -0,0 +1,290 @@
+use crate::cls_file::{is_valid_header_value, ClsBodyBuilder};
+use crate::project_files::{extension, read_meta, ProjectFiles};
+use crate::{find_yaml_value, ReplaceSet};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AssemblyDefinition {
+    name: String,
+    #[serde(default)]
+    references: Vec<String>,
+    #[serde(default)]
+    include_platforms: Vec<String>,
+    #[serde(default)]
+    exclude_platforms: Vec<String>,
+    #[serde(default)]
+    define_constraints: Vec<String>,
+    #[serde(default)]
+    version_defines: Vec<VersionDefine>,
+}
+
+#[derive(Deserialize)]
+struct VersionDefine {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    expression: String,
+    #[serde(default)]
+    define: String,
+}
+
+#[derive(Deserialize)]
+struct AssemblyDefinitionReference {
+    reference: String,
+}
+
+struct AssemblyFile {
+    path: PathBuf,
+    content: String,
+    parsed: Result<Parsed, String>,
+}
+
+enum Parsed {
+    Definition(AssemblyDefinition),
+    Reference(AssemblyDefinitionReference),
+}
+
+impl Parsed {
+    /// Returns the field with a line break, which cannot be saved as a header.
+    fn field_with_line_break(&self) -> Option<&'static str> {
+        fn has_line_break<'a>(mut values: impl Iterator<Item = &'a String>) -> bool {
+            values.any(|x| !is_valid_header_value(x))
+        }
+
+        match self {
+            Parsed::Reference(reference) => {
+                (!is_valid_header_value(&reference.reference)).then_some("reference")
+            }
+            Parsed::Definition(definition) => {
+                let version_defines = (definition.version_defines.iter())
+                    .flat_map(|x| [&x.name, &x.expression, &x.define]);
+                if !is_valid_header_value(&definition.name) {
+                    Some("name")
+                } else if has_line_break(definition.references.iter()) {
+                    Some("references")
+                } else if has_line_break(definition.include_platforms.iter()) {
+                    Some("includePlatforms")
+                } else if has_line_break(definition.exclude_platforms.iter()) {
+                    Some("excludePlatforms")
+                } else if has_line_break(definition.define_constraints.iter()) {
+                    Some("defineConstraints")
+                } else if has_line_break(version_defines) {
+                    Some("versionDefines")
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// Appends `assembly-definition` and `assembly-definition-reference` sections
+/// for the asmdef and asmref files in `Assets` and packages.
+pub(crate) fn append_assembly_graph(
+    builder: &mut ClsBodyBuilder,
+    cwd: &str,
+    replacer: &ReplaceSet,
+) {
+    let project_files = ProjectFiles::new(Path::new(cwd));
+
+    let mut files = Vec::new();
+    project_files.walk(|path, is_dir| {
+        let is_definition = match extension(&path).as_deref() {
+            _ if is_dir => return true,
+            Some("asmdef") => true,
+            Some("asmref") => false,
+            _ => return false,
+        };
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return false;
+        };
+        // asmdefs are often saved with BOM
+        let content = content.trim_start_matches('\u{feff}').to_owned();
+        let parsed = if is_definition {
+            serde_json::from_str(&content).map(Parsed::Definition)
+        } else {
+            serde_json::from_str(&content).map(Parsed::Reference)
+        };
+        // the values are saved as headers, so line breaks would break the file
+        let parsed = parsed.map_err(|err| err.to_string()).and_then(|parsed| {
+            match parsed.field_with_line_break() {
+                Some(field) => Err(format!("line break in {field}")),
+                None => Ok(parsed),
+            }
+        });
+        files.push(AssemblyFile {
+            path,
+            content,
+            parsed,
+        });
+        false
+    });
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    // references are names or `GUID:` with the guid of the asmdef
+    let mut names = BTreeSet::new();
+    let mut guids = HashMap::new();
+    for file in &files {
+        let Ok(Parsed::Definition(definition)) = &file.parsed else {
+            continue;
+        };
+        names.insert(definition.name.as_str());
+        if let Some(guid) = read_meta(&file.path)
+            .as_deref()
+            .and_then(|x| find_yaml_value(x, "guid"))
+        {
+            guids.insert(guid.to_ascii_lowercase(), definition.name.as_str());
+        }
+    }
+    let resolve = |reference: &str| -> Option<&str> {
+        match reference.strip_prefix("GUID:") {
+            Some(guid) => guids.get(&guid.to_ascii_lowercase()).copied(),
+            None => names.get(reference).copied(),
+        }
+    };
+
+    let mut graph = BTreeMap::<&str, BTreeSet<&str>>::new();
+    for file in &files {
+        let Ok(Parsed::Definition(definition)) = &file.parsed else {
+            continue;
+        };
+        let edges = graph.entry(definition.name.as_str()).or_default();
+        edges.extend(definition.references.iter().filter_map(|x| resolve(x)));
+    }
+    let cycles = find_cycles(&graph);
+
+    for file in &files {
+        builder.add_header("Path", &project_files.display_path(&file.path, replacer));
+
+        let add_reference = |builder: &mut ClsBodyBuilder, reference: &str| {
+            let resolved = resolve(reference);
+            builder.add_header("Reference", resolved.unwrap_or(reference));
+            if resolved.is_none() {
+                builder.add_header("Missing-Reference", reference);
+            }
+        };
+
+        let content_type = match &file.parsed {
+            Err(err) => {
+                builder.add_header("Parse-Error", err);
+                if extension(&file.path).as_deref() == Some("asmdef") {
+                    "assembly-definition"
+                } else {
+                    "assembly-definition-reference"
+                }
+            }
+            Ok(Parsed::Reference(reference)) => {
+                add_reference(builder, &reference.reference);
+                "assembly-definition-reference"
+            }
+            Ok(Parsed::Definition(definition)) => {
+                builder.add_header("Name", &definition.name);
+                for reference in &definition.references {
+                    add_reference(builder, reference);
+                }
+                for constraint in &definition.define_constraints {
+                    builder.add_header("Define-Constraint", constraint);
+                }
+                for platform in &definition.include_platforms {
+                    builder.add_header("Include-Platform", platform);
+                }
+                for platform in &definition.exclude_platforms {
+                    builder.add_header("Exclude-Platform", platform);
+                }
+                for version_define in &definition.version_defines {
+                    let VersionDefine {
+                        name,
+                        expression,
+                        define,
+                    } = version_define;
+                    let expression = if expression.is_empty() {
+                        "-"
+                    } else {
+                        expression
+                    };
+                    let value = format!("{name} {expression} {define}");
+                    builder.add_header("Version-Define", &value);
+                }
+                if let Some(cycle) = cycles.get(definition.name.as_str()) {
+                    builder.add_header("Reference-Cycle", &cycle.join(", "));
+                }
+                "assembly-definition"
+            }
+        };
+
+        builder.add_content(
+            content_type,
+            &replacer.replace_all(Cow::Borrowed(&file.content)),
+        );
+    }
+}
+
+/// Finds the assemblies referencing each other with Tarjan's algorithm.
+/// Returns the assemblies in the same cycle for each assembly in a cycle.
+fn find_cycles<'a>(graph: &BTreeMap<&'a str, BTreeSet<&'a str>>) -> HashMap<&'a str, Vec<&'a str>> {
+    struct State<'a, 'g> {
+        graph: &'g BTreeMap<&'a str, BTreeSet<&'a str>>,
+        index: HashMap<&'a str, usize>,
+        low_link: HashMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        on_stack: BTreeSet<&'a str>,
+        cycles: HashMap<&'a str, Vec<&'a str>>,
+    }
+
+    fn visit<'a>(state: &mut State<'a, '_>, node: &'a str) {
+        let index = state.index.len();
+        state.index.insert(node, index);
+        state.low_link.insert(node, index);
+        state.stack.push(node);
+        state.on_stack.insert(node);
+
+        let graph = state.graph;
+        for &next in graph.get(node).into_iter().flatten() {
+            if !state.index.contains_key(next) {
+                visit(state, next);
+                let low_link = state.low_link[node].min(state.low_link[next]);
+                state.low_link.insert(node, low_link);
+            } else if state.on_stack.contains(next) {
+                let low_link = state.low_link[node].min(state.index[next]);
+                state.low_link.insert(node, low_link);
+            }
+        }
+
+        if state.low_link[node] == state.index[node] {
+            let mut component = Vec::new();
+            while let Some(member) = state.stack.pop() {
+                state.on_stack.remove(member);
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            let self_reference = graph.get(node).is_some_and(|x| x.contains(node));
+            if component.len() > 1 || self_reference {
+                component.sort();
+                for &member in &component {
+                    state.cycles.insert(member, component.clone());
+                }
+            }
+        }
+    }
+
+    let mut state = State {
+        graph,
+        index: HashMap::new(),
+        low_link: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: BTreeSet::new(),
+        cycles: HashMap::new(),
+    };
+    for &node in graph.keys() {
+        if !state.index.contains_key(node) {
+            visit(&mut state, node);
+        }
+    }
+    state.cycles
+}
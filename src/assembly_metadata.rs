@@ -0,0 +1,268 @@
+//! Minimal reader of the assembly name and version in the ECMA-335 metadata of managed DLLs.
+
+pub(crate) struct AssemblyInfo {
+    pub name: String,
+    pub version: (u16, u16, u16, u16),
+}
+
+/// Reads the assembly info. Returns None for native DLLs or if the file is broken.
+pub(crate) fn read_assembly_info(data: &[u8]) -> Option<AssemblyInfo> {
+    let metadata = find_metadata(data)?;
+    read_assembly_table(metadata)
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+/// Finds the metadata root with the CLI header of the PE file.
+fn find_metadata(data: &[u8]) -> Option<&[u8]> {
+    if data.get(0..2)? != b"MZ" {
+        return None;
+    }
+    let pe_offset = u32_at(data, 0x3c)? as usize;
+    if data.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+        return None;
+    }
+    let coff = pe_offset + 4;
+    let section_count = u16_at(data, coff + 2)? as usize;
+    let optional_header_size = u16_at(data, coff + 16)? as usize;
+    let optional_header = coff + 20;
+    let data_directories = match u16_at(data, optional_header)? {
+        0x10b => optional_header + 96,  // PE32
+        0x20b => optional_header + 112, // PE32+
+        _ => return None,
+    };
+    // the 15th data directory is the CLI header
+    let cli_header_rva = u32_at(data, data_directories + 14 * 8)?;
+    if cli_header_rva == 0 {
+        return None; // native
+    }
+
+    let sections = optional_header + optional_header_size;
+    let rva_to_offset = |rva: u32| -> Option<usize> {
+        (0..section_count).find_map(|index| {
+            let section = sections + index * 40;
+            let virtual_size = u32_at(data, section + 8)?;
+            let virtual_address = u32_at(data, section + 12)?;
+            let raw_size = u32_at(data, section + 16)?;
+            let raw_pointer = u32_at(data, section + 20)?;
+            let size = virtual_size.max(raw_size);
+            // the values may be corrupted
+            let end = virtual_address.checked_add(size)?;
+            (virtual_address <= rva && rva < end)
+                .then(|| (rva - virtual_address).checked_add(raw_pointer))
+                .flatten()
+                .map(|x| x as usize)
+        })
+    };
+
+    let cli_header = rva_to_offset(cli_header_rva)?;
+    let metadata_rva = u32_at(data, cli_header + 8)?;
+    let metadata_size = u32_at(data, cli_header + 12)? as usize;
+    let metadata = rva_to_offset(metadata_rva)?;
+    data.get(metadata..metadata.checked_add(metadata_size)?)
+}
+
+const TABLE_MODULE: usize = 0x00;
+const TABLE_TYPE_REF: usize = 0x01;
+const TABLE_TYPE_DEF: usize = 0x02;
+const TABLE_FIELD: usize = 0x04;
+const TABLE_METHOD_DEF: usize = 0x06;
+const TABLE_PARAM: usize = 0x08;
+const TABLE_INTERFACE_IMPL: usize = 0x09;
+const TABLE_MEMBER_REF: usize = 0x0a;
+const TABLE_DECL_SECURITY: usize = 0x0e;
+const TABLE_STAND_ALONE_SIG: usize = 0x11;
+const TABLE_EVENT: usize = 0x14;
+const TABLE_PROPERTY: usize = 0x17;
+const TABLE_MODULE_REF: usize = 0x1a;
+const TABLE_TYPE_SPEC: usize = 0x1b;
+const TABLE_ASSEMBLY: usize = 0x20;
+const TABLE_ASSEMBLY_REF: usize = 0x23;
+const TABLE_FILE: usize = 0x26;
+const TABLE_EXPORTED_TYPE: usize = 0x27;
+const TABLE_MANIFEST_RESOURCE: usize = 0x28;
+const TABLE_GENERIC_PARAM: usize = 0x2a;
+const TABLE_METHOD_SPEC: usize = 0x2b;
+const TABLE_GENERIC_PARAM_CONSTRAINT: usize = 0x2c;
+
+fn read_assembly_table(metadata: &[u8]) -> Option<AssemblyInfo> {
+    if u32_at(metadata, 0)? != 0x424a5342 {
+        return None;
+    }
+    let version_length = u32_at(metadata, 12)? as usize;
+    let stream_count = u16_at(metadata, 16 + version_length + 2)? as usize;
+
+    let mut offset = 16 + version_length + 4;
+    let mut tables = None;
+    let mut strings = None;
+    for _ in 0..stream_count {
+        let stream_offset = u32_at(metadata, offset)? as usize;
+        let stream_size = u32_at(metadata, offset + 4)? as usize;
+        let name_start = offset + 8;
+        let name_length = metadata.get(name_start..)?.iter().position(|&x| x == 0)?;
+        let name = metadata.get(name_start..name_start + name_length)?;
+        let stream = metadata.get(stream_offset..stream_offset + stream_size)?;
+        match name {
+            b"#~" | b"#-" => tables = Some(stream),
+            b"#Strings" => strings = Some(stream),
+            _ => {}
+        }
+        // names are padded to 4 bytes
+        offset = name_start + (name_length + 4) / 4 * 4;
+    }
+    let tables = tables?;
+    let strings = strings?;
+
+    let heap_sizes = *tables.get(6)?;
+    let valid = u64::from_le_bytes(tables.get(8..16)?.try_into().ok()?);
+    let mut rows = [0u32; 64];
+    let mut offset = 24;
+    for (table, row) in rows.iter_mut().enumerate() {
+        if valid & (1 << table) != 0 {
+            *row = u32_at(tables, offset)?;
+            offset += 4;
+        }
+    }
+    if heap_sizes & 0x40 != 0 {
+        offset += 4; // extra data
+    }
+    if rows[TABLE_ASSEMBLY] == 0 {
+        return None; // netmodule
+    }
+
+    let string = if heap_sizes & 0x01 != 0 { 4 } else { 2 };
+    let guid = if heap_sizes & 0x02 != 0 { 4 } else { 2 };
+    let blob = if heap_sizes & 0x04 != 0 { 4 } else { 2 };
+    let index = |table: usize| if rows[table] < 0x10000 { 2 } else { 4 };
+    let coded = |tables: &[usize]| {
+        let tag_bits = usize::BITS - (tables.len() - 1).leading_zeros();
+        let max = tables.iter().map(|&x| rows[x]).max().unwrap_or(0);
+        if max < (1 << (16 - tag_bits)) {
+            2
+        } else {
+            4
+        }
+    };
+
+    let resolution_scope = coded(&[
+        TABLE_MODULE,
+        TABLE_MODULE_REF,
+        TABLE_ASSEMBLY_REF,
+        TABLE_TYPE_REF,
+    ]);
+    let type_def_or_ref = coded(&[TABLE_TYPE_DEF, TABLE_TYPE_REF, TABLE_TYPE_SPEC]);
+    let member_ref_parent = coded(&[
+        TABLE_TYPE_DEF,
+        TABLE_TYPE_REF,
+        TABLE_MODULE_REF,
+        TABLE_METHOD_DEF,
+        TABLE_TYPE_SPEC,
+    ]);
+    let has_constant = coded(&[TABLE_FIELD, TABLE_PARAM, TABLE_PROPERTY]);
+    let has_custom_attribute = coded(&[
+        TABLE_METHOD_DEF,
+        TABLE_FIELD,
+        TABLE_TYPE_REF,
+        TABLE_TYPE_DEF,
+        TABLE_PARAM,
+        TABLE_INTERFACE_IMPL,
+        TABLE_MEMBER_REF,
+        TABLE_MODULE,
+        TABLE_DECL_SECURITY,
+        TABLE_PROPERTY,
+        TABLE_EVENT,
+        TABLE_STAND_ALONE_SIG,
+        TABLE_MODULE_REF,
+        TABLE_TYPE_SPEC,
+        TABLE_ASSEMBLY,
+        TABLE_ASSEMBLY_REF,
+        TABLE_FILE,
+        TABLE_EXPORTED_TYPE,
+        TABLE_MANIFEST_RESOURCE,
+        TABLE_GENERIC_PARAM,
+        TABLE_GENERIC_PARAM_CONSTRAINT,
+        TABLE_METHOD_SPEC,
+    ]);
+    // CustomAttributeType has 5 tags but only MethodDef and MemberRef are used
+    let custom_attribute_type = coded(&[
+        TABLE_METHOD_DEF,
+        TABLE_METHOD_DEF,
+        TABLE_METHOD_DEF,
+        TABLE_MEMBER_REF,
+        TABLE_MEMBER_REF,
+    ]);
+    let has_field_marshal = coded(&[TABLE_FIELD, TABLE_PARAM]);
+    let has_decl_security = coded(&[TABLE_TYPE_DEF, TABLE_METHOD_DEF, TABLE_ASSEMBLY]);
+    let has_semantics = coded(&[TABLE_EVENT, TABLE_PROPERTY]);
+    let method_def_or_ref = coded(&[TABLE_METHOD_DEF, TABLE_MEMBER_REF]);
+    let member_forwarded = coded(&[TABLE_FIELD, TABLE_METHOD_DEF]);
+
+    // the row sizes of the tables before the Assembly table
+    let row_sizes: [usize; TABLE_ASSEMBLY] = [
+        2 + string + guid * 3,                                        // Module
+        resolution_scope + string * 2,                                // TypeRef
+        4 + string * 2 + type_def_or_ref + index(0x04) + index(0x06), // TypeDef
+        index(0x04),                                                  // FieldPtr
+        2 + string + blob,                                            // Field
+        index(0x06),                                                  // MethodPtr
+        4 + 2 + 2 + string + blob + index(0x08),                      // MethodDef
+        index(0x08),                                                  // ParamPtr
+        2 + 2 + string,                                               // Param
+        index(0x02) + type_def_or_ref,                                // InterfaceImpl
+        member_ref_parent + string + blob,                            // MemberRef
+        2 + has_constant + blob,                                      // Constant
+        has_custom_attribute + custom_attribute_type + blob,          // CustomAttribute
+        has_field_marshal + blob,                                     // FieldMarshal
+        2 + has_decl_security + blob,                                 // DeclSecurity
+        2 + 4 + index(0x02),                                          // ClassLayout
+        4 + index(0x04),                                              // FieldLayout
+        blob,                                                         // StandAloneSig
+        index(0x02) + index(0x14),                                    // EventMap
+        index(0x14),                                                  // EventPtr
+        2 + string + type_def_or_ref,                                 // Event
+        index(0x02) + index(0x17),                                    // PropertyMap
+        index(0x17),                                                  // PropertyPtr
+        2 + string + blob,                                            // Property
+        2 + index(0x06) + has_semantics,                              // MethodSemantics
+        index(0x02) + method_def_or_ref * 2,                          // MethodImpl
+        string,                                                       // ModuleRef
+        blob,                                                         // TypeSpec
+        2 + member_forwarded + string + index(0x1a),                  // ImplMap
+        4 + index(0x04),                                              // FieldRVA
+        4 + 4,                                                        // EncLog
+        4,                                                            // EncMap
+    ];
+    for (table, row_size) in row_sizes.iter().enumerate() {
+        offset += row_size * rows[table] as usize;
+    }
+
+    // Assembly: HashAlgId, Version, Flags, PublicKey, Name, Culture
+    let row = offset;
+    let major = u16_at(tables, row + 4)?;
+    let minor = u16_at(tables, row + 6)?;
+    let build = u16_at(tables, row + 8)?;
+    let revision = u16_at(tables, row + 10)?;
+    let name_offset = row + 16 + blob;
+    let name_index = if string == 4 {
+        u32_at(tables, name_offset)? as usize
+    } else {
+        u16_at(tables, name_offset)? as usize
+    };
+    let name = strings.get(name_index..)?;
+    let name = &name[..name.iter().position(|&x| x == 0)?];
+
+    Some(AssemblyInfo {
+        name: String::from_utf8_lossy(name).into_owned(),
+        version: (major, minor, build, revision),
+    })
+}
@@ -0,0 +1,333 @@
+use crate::{ConsoleLogSaverConfig, ReplaceSet};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+// The bundle is a zip file with the following files
+//
+// manifest.json: the format, and the names, sizes and SHA-256 hashes of the files below
+// console-log.txt: the CLS file
+// attachments/<name>: the attached files, redacted if they are text
+
+const FORMAT: &str = "ConsoleLogSaverBundle/1.0";
+const FORMAT_PREFIX: &str = "ConsoleLogSaverBundle/1.";
+const MANIFEST_NAME: &str = "manifest.json";
+const CONSOLE_LOG_NAME: &str = "console-log.txt";
+const ATTACHMENTS_DIR: &str = "attachments/";
+/// The manifest is read before the sizes are known, so a large manifest is rejected.
+const MAX_MANIFEST_SIZE: u64 = 1024 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid zip file: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("no manifest.json in the bundle")]
+    ManifestNotFound,
+    #[error("manifest.json is too large")]
+    ManifestTooLarge,
+    #[error("invalid manifest.json: {0}")]
+    InvalidManifest(#[from] serde_json::Error),
+    #[error("unsupported bundle format: {0}")]
+    UnsupportedFormat(String),
+    #[error("invalid file name in the bundle: {0}")]
+    InvalidName(String),
+    #[error("{0} in the manifest is not in the bundle")]
+    MissingFile(String),
+    #[error("{0} is in the bundle but not in the manifest")]
+    UnlistedFile(String),
+    #[error("{0} does not match the size or hash in the manifest")]
+    HashMismatch(String),
+    #[error("the console log is not valid UTF-8")]
+    NonUtf8ConsoleLog,
+}
+
+/// Whether the personal information in the attachment is hidden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AttachmentRedaction {
+    /// The attachment is text and the redaction is applied.
+    Redacted,
+    /// The attachment is binary like screenshots or crash dumps, or no rules hiding
+    /// the personal information are enabled, and saved as is.
+    Unredacted,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Manifest {
+    format: String,
+    console_log: ManifestFile,
+    #[serde(default)]
+    attachments: Vec<ManifestAttachment>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ManifestFile {
+    name: String,
+    size: u64,
+    sha256: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ManifestAttachment {
+    #[serde(flatten)]
+    file: ManifestFile,
+    redaction: AttachmentRedaction,
+}
+
+impl ManifestFile {
+    fn new(name: String, data: &[u8]) -> Self {
+        Self {
+            name,
+            size: data.len() as u64,
+            sha256: sha256_hex(data),
+        }
+    }
+
+    fn matches(&self, data: &[u8]) -> bool {
+        self.size == data.len() as u64 && self.sha256.eq_ignore_ascii_case(&sha256_hex(data))
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|x| format!("{x:02x}"))
+        .collect()
+}
+
+/// Creates the bundle of the CLS file and the attachments.
+///
+/// The redaction of the config is applied to the names and text attachments.
+pub struct ClsBundleBuilder<'a> {
+    replacer: ReplaceSet<'a>,
+    hides_personal_info: bool,
+    attachments: Vec<(String, Vec<u8>, AttachmentRedaction)>,
+}
+
+impl<'a> ClsBundleBuilder<'a> {
+    pub fn new(config: &'a ConsoleLogSaverConfig) -> Self {
+        Self {
+            replacer: ReplaceSet::new(config),
+            // the always enabled rules are for secrets, not the personal information
+            hides_personal_info: config.hide_user_name
+                || config.hide_user_home
+                || !config.custom_rules.is_empty(),
+            attachments: Vec::new(),
+        }
+    }
+
+    /// Attaches the data with the file name.
+    /// The name is suffixed with a number if the name is already used.
+    pub fn attach(&mut self, name: &str, data: Vec<u8>) -> &mut Self {
+        let name = self.attachment_name(name);
+        let (data, redaction) = match String::from_utf8(data) {
+            Ok(text) => {
+                let text = self.replacer.replace_all(Cow::Owned(text));
+                let redaction = if self.hides_personal_info {
+                    AttachmentRedaction::Redacted
+                } else {
+                    AttachmentRedaction::Unredacted
+                };
+                (text.into_owned().into_bytes(), redaction)
+            }
+            Err(err) => (err.into_bytes(), AttachmentRedaction::Unredacted),
+        };
+        self.attachments.push((name, data, redaction));
+        self
+    }
+
+    /// Attaches the file with its file name.
+    pub fn attach_file(&mut self, path: &Path) -> std::io::Result<&mut Self> {
+        let data = std::fs::read(path)?;
+        let name = path
+            .file_name()
+            .map(|x| x.to_string_lossy())
+            .unwrap_or_default();
+        Ok(self.attach(&name, data))
+    }
+
+    fn attachment_name(&self, name: &str) -> String {
+        let name = self.replacer.replace_all(Cow::Borrowed(name));
+        let name = name.replace(['/', '\\'], "_");
+        let name = match name.as_str() {
+            "" | "." | ".." => "attachment".to_owned(),
+            _ => name,
+        };
+
+        let used = |name: &str| self.attachments.iter().any(|(x, _, _)| x == name);
+        if !used(&name) {
+            return name;
+        }
+        let (stem, extension) = match name.rfind('.').filter(|&x| x > 0) {
+            Some(index) => name.split_at(index),
+            None => (name.as_str(), ""),
+        };
+        (2..)
+            .map(|number| format!("{stem}-{number}{extension}"))
+            .find(|x| !used(x))
+            .unwrap()
+    }
+
+    /// Writes the bundle with the CLS file as a zip file.
+    /// The CLS file may be encrypted with [`encrypt_cls_file`](crate::encrypt_cls_file).
+    pub fn write(&self, cls_file: &str, writer: impl Write + Seek) -> Result<(), BundleError> {
+        let manifest = Manifest {
+            format: FORMAT.to_owned(),
+            console_log: ManifestFile::new(CONSOLE_LOG_NAME.to_owned(), cls_file.as_bytes()),
+            attachments: (self.attachments.iter())
+                .map(|(name, data, redaction)| ManifestAttachment {
+                    file: ManifestFile::new(format!("{ATTACHMENTS_DIR}{name}"), data),
+                    redaction: *redaction,
+                })
+                .collect(),
+        };
+
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut zip = ZipWriter::new(writer);
+        zip.start_file(MANIFEST_NAME, options)?;
+        zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+        zip.start_file(CONSOLE_LOG_NAME, options)?;
+        zip.write_all(cls_file.as_bytes())?;
+        for ((_, data, _), attachment) in self.attachments.iter().zip(&manifest.attachments) {
+            zip.start_file(attachment.file.name.as_str(), options)?;
+            zip.write_all(data)?;
+        }
+        zip.finish()?;
+        Ok(())
+    }
+}
+
+/// The bundle read and validated with the manifest.
+pub struct ClsBundle {
+    console_log: String,
+    attachments: Vec<BundleAttachment>,
+}
+
+pub struct BundleAttachment {
+    name: String,
+    redaction: AttachmentRedaction,
+    data: Vec<u8>,
+}
+
+impl ClsBundle {
+    /// Reads the bundle and checks all the files in the bundle match the manifest.
+    pub fn read(reader: impl Read + Seek) -> Result<Self, BundleError> {
+        let mut zip = ZipArchive::new(reader)?;
+
+        let manifest = read_zip_file(&mut zip, MANIFEST_NAME, MAX_MANIFEST_SIZE)?
+            .ok_or(BundleError::ManifestNotFound)?;
+        if manifest.len() as u64 > MAX_MANIFEST_SIZE {
+            return Err(BundleError::ManifestTooLarge);
+        }
+        let manifest: Manifest = serde_json::from_slice(&manifest)?;
+        if !manifest.format.starts_with(FORMAT_PREFIX) {
+            return Err(BundleError::UnsupportedFormat(manifest.format));
+        }
+
+        let mut listed = HashSet::from([MANIFEST_NAME.to_owned()]);
+        let mut read_listed = |file: &ManifestFile| -> Result<Vec<u8>, BundleError> {
+            if !listed.insert(file.name.clone()) {
+                return Err(BundleError::InvalidName(file.name.clone()));
+            }
+            // the larger data is rejected by the size check in matches
+            let data = read_zip_file(&mut zip, &file.name, file.size)?
+                .ok_or_else(|| BundleError::MissingFile(file.name.clone()))?;
+            if !file.matches(&data) {
+                return Err(BundleError::HashMismatch(file.name.clone()));
+            }
+            Ok(data)
+        };
+
+        let console_log = read_listed(&manifest.console_log)?;
+        let console_log =
+            String::from_utf8(console_log).map_err(|_| BundleError::NonUtf8ConsoleLog)?;
+
+        let mut attachments = Vec::with_capacity(manifest.attachments.len());
+        for attachment in &manifest.attachments {
+            // the name is used as the file name when unpacking
+            let name = (attachment.file.name.strip_prefix(ATTACHMENTS_DIR))
+                .filter(|x| !matches!(*x, "" | "." | "..") && !x.contains(['/', '\\']))
+                .ok_or_else(|| BundleError::InvalidName(attachment.file.name.clone()))?;
+            attachments.push(BundleAttachment {
+                name: name.to_owned(),
+                redaction: attachment.redaction,
+                data: read_listed(&attachment.file)?,
+            });
+        }
+
+        if let Some(unlisted) = zip
+            .file_names()
+            .find(|x| !x.ends_with('/') && !listed.contains(*x))
+        {
+            return Err(BundleError::UnlistedFile(unlisted.to_owned()));
+        }
+
+        Ok(Self {
+            console_log,
+            attachments,
+        })
+    }
+
+    /// The CLS file in the bundle, which may be encrypted.
+    pub fn console_log(&self) -> &str {
+        &self.console_log
+    }
+
+    pub fn attachments(&self) -> &[BundleAttachment] {
+        &self.attachments
+    }
+
+    /// Writes the CLS file as `console-log.txt` and the attachments into `attachments` in the directory.
+    pub fn unpack(&self, directory: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(directory.join(ATTACHMENTS_DIR))?;
+        std::fs::write(directory.join(CONSOLE_LOG_NAME), &self.console_log)?;
+        for attachment in &self.attachments {
+            let path = directory.join(ATTACHMENTS_DIR).join(&attachment.name);
+            std::fs::write(path, &attachment.data)?;
+        }
+        Ok(())
+    }
+}
+
+impl BundleAttachment {
+    /// The file name of the attachment.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn redaction(&self) -> AttachmentRedaction {
+        self.redaction
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// Reads the file up to `max_size + 1` bytes, so the caller can reject the larger file.
+/// The size in the zip file is not trusted since it's not validated until the end of the file.
+fn read_zip_file<R: Read + Seek>(
+    zip: &mut ZipArchive<R>,
+    name: &str,
+    max_size: u64,
+) -> Result<Option<Vec<u8>>, BundleError> {
+    let file = match zip.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let mut data = Vec::new();
+    file.take(max_size.saturating_add(1))
+        .read_to_end(&mut data)?;
+    Ok(Some(data))
+}
@@ -0,0 +1,51 @@
+use crate::ClsFile;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The position in the console at a capture, saved as `Capture-Cursor` header.
+///
+/// Passing the cursor of the previous capture to [`ConsoleLogSaverConfig::since`]
+/// saves the entries logged after the previous capture only.
+///
+/// [`ConsoleLogSaverConfig::since`]: crate::ConsoleLogSaverConfig::since
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CaptureCursor {
+    pub(crate) count: i32,
+    pub(crate) fingerprint: u64,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid capture cursor")]
+pub struct InvalidCaptureCursor;
+
+impl CaptureCursor {
+    /// Reads the cursor from the `Capture-Cursor` header of the CLS file.
+    pub fn from_cls_file(cls_file: &ClsFile) -> Option<CaptureCursor> {
+        cls_file.header("Capture-Cursor")?.parse().ok()
+    }
+
+    /// The number of entries in the console at the capture.
+    pub fn count(&self) -> usize {
+        self.count as usize
+    }
+}
+
+impl Display for CaptureCursor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{:016x}", self.count, self.fingerprint)
+    }
+}
+
+impl FromStr for CaptureCursor {
+    type Err = InvalidCaptureCursor;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (count, fingerprint) = s.trim().split_once(':').ok_or(InvalidCaptureCursor)?;
+        let count = count.parse::<i32>().map_err(|_| InvalidCaptureCursor)?;
+        if count < 0 {
+            return Err(InvalidCaptureCursor);
+        }
+        let fingerprint = u64::from_str_radix(fingerprint, 16).map_err(|_| InvalidCaptureCursor)?;
+        Ok(CaptureCursor { count, fingerprint })
+    }
+}
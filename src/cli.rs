@@ -1,30 +1,160 @@
 use console_log_saver::{
-    find_unity_processes, run_console_log_saver, ConsoleLogSaverConfig, ProcessId,
+    decrypt_cls_file, encrypt_cls_file, find_unity_processes_in, find_unity_processes_of_kinds,
+    is_encrypted_cls_file, redact_cls_file, run_console_log_saver, run_offline_console_log_saver,
+    unity_hub_editors, unity_hub_projects, watch_console_log_saver, AttachmentRedaction,
+    CaptureCursor, ClsBundle, ClsBundleBuilder, ClsFile, ConsoleLogSaverConfig, ProcessId,
+    RedactionRule, UnityProcess, UnityProcessKind, WatchCapture,
 };
+use std::io::IsTerminal;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::time::{Duration, SystemTime};
 
 fn main() {
     let mut settings = ConsoleLogSaverConfig::default();
     let mut pid = None;
+    let mut encrypt = false;
+    let mut decrypt = None;
+    let mut passphrase = None;
+    let mut watch = None;
+    let mut all = false;
+    let mut project = None;
+    let mut list = false;
+    let mut json = false;
+    let mut include_helpers = false;
+    let mut hub_projects = false;
+    let mut offline = None;
+    let mut search_roots = Vec::new();
+    let mut output_dir = PathBuf::from(".");
+    let mut bundle = None;
+    let mut attachments = Vec::new();
+    let mut unpack_bundle = None;
+    let mut watch_options = WatchOptions {
+        directory: PathBuf::from("."),
+        keep: 20,
+        snapshot_on_error: false,
+    };
 
     let mut args = std::env::args();
     let exe = args.next().unwrap();
 
+    let mut args = args.peekable();
+    if args.peek().map(|x| x == "redact").unwrap_or(false) {
+        args.next();
+        redact_main(&exe, args);
+    }
+
     while let Some(arg) = args.next() {
         match arg.as_str() {
-            "--hide-user-name" => settings.hide_user_name = true,
-            "--show-user-name" => settings.hide_user_name = false,
-            "--hide-user-home" => settings.hide_user_home = true,
-            "--show-user-home" => settings.hide_user_home = false,
-            "--hide-os-info" => settings.hide_os_info = true,
-            "--show-os-info" => settings.hide_os_info = false,
-            "--hide-aws-upload-signature" => settings.hide_aws_upload_signature = true,
-            "--show-aws-upload-signature" => settings.hide_aws_upload_signature = false,
-            "--list" => {
-                print_processes();
-                exit(0);
+            arg if parse_config_option(arg, &mut args, &mut settings) => {}
+            "--list" => list = true,
+            "--json" => json = true,
+            "--include-helpers" => include_helpers = true,
+            "--hub-projects" => hub_projects = true,
+            "--offline" => {
+                let Some(path_or_name) = args.next() else {
+                    eprintln!("No opeand found for --offline");
+                    exit(1);
+                };
+                offline = Some(path_or_name);
+            }
+            "--search-root" => {
+                let Some(root) = args.next() else {
+                    eprintln!("No opeand found for --search-root");
+                    exit(1);
+                };
+                search_roots.push(PathBuf::from(root));
+            }
+            "--project" => {
+                let Some(path_or_name) = args.next() else {
+                    eprintln!("No opeand found for --project");
+                    exit(1);
+                };
+                project = Some(path_or_name);
             }
             "--help" | "-h" => print_help(&exe, 0),
+            "--encrypt" => encrypt = true,
+            "--decrypt" => {
+                let Some(path) = args.next() else {
+                    eprintln!("No opeand found for --decrypt");
+                    exit(1);
+                };
+                decrypt = Some(path);
+            }
+            "--passphrase" => {
+                let Some(value) = args.next() else {
+                    eprintln!("No opeand found for --passphrase");
+                    exit(1);
+                };
+                passphrase = Some(value);
+            }
+            "--watch" => {
+                let Some(interval) = args.next() else {
+                    eprintln!("No opeand found for --watch");
+                    exit(1);
+                };
+                let Some(interval) = interval.parse::<f64>().ok().filter(|x| *x > 0.0) else {
+                    eprintln!("Invalid interval: {interval}");
+                    exit(1);
+                };
+                watch = Some(Duration::from_secs_f64(interval));
+            }
+            "--watch-dir" => {
+                let Some(directory) = args.next() else {
+                    eprintln!("No opeand found for --watch-dir");
+                    exit(1);
+                };
+                watch_options.directory = PathBuf::from(directory);
+            }
+            "--watch-keep" => {
+                let Some(keep) = args.next() else {
+                    eprintln!("No opeand found for --watch-keep");
+                    exit(1);
+                };
+                let Some(keep) = keep.parse::<usize>().ok().filter(|x| *x > 0) else {
+                    eprintln!("Invalid count: {keep}");
+                    exit(1);
+                };
+                watch_options.keep = keep;
+            }
+            "--snapshot-on-error" => watch_options.snapshot_on_error = true,
+            "--all" => all = true,
+            "--output-dir" => {
+                let Some(directory) = args.next() else {
+                    eprintln!("No opeand found for --output-dir");
+                    exit(1);
+                };
+                output_dir = PathBuf::from(directory);
+            }
+            "--bundle" => {
+                let Some(path) = args.next() else {
+                    eprintln!("No opeand found for --bundle");
+                    exit(1);
+                };
+                bundle = Some(PathBuf::from(path));
+            }
+            "--attach" => {
+                let Some(path) = args.next() else {
+                    eprintln!("No opeand found for --attach");
+                    exit(1);
+                };
+                attachments.push(PathBuf::from(path));
+            }
+            "--unpack-bundle" => {
+                let Some(path) = args.next() else {
+                    eprintln!("No opeand found for --unpack-bundle");
+                    exit(1);
+                };
+                unpack_bundle = Some(path);
+            }
+            "--since" => {
+                let Some(since) = args.next() else {
+                    eprintln!("No opeand found for --since");
+                    exit(1);
+                };
+                settings.since = Some(parse_cursor(&since));
+            }
             "--pid" => {
                 let Some(pid_str) = args.next() else {
                     eprintln!("No opeand found for --pid");
@@ -55,8 +185,107 @@ fn main() {
         }
     }
 
+    if let Some(path) = decrypt {
+        let encrypted = read_file(&path);
+        match decrypt_cls_file(&encrypted, &read_passphrase(passphrase)) {
+            Ok(decrypted) => print!("{decrypted}"),
+            Err(err) => {
+                eprintln!("failed to decrypt {path}: {err}");
+                exit(1);
+            }
+        }
+        exit(0);
+    }
+
+    if let Some(path) = unpack_bundle {
+        unpack_bundle_main(&path, &output_dir);
+    }
+
+    let bundle = match bundle {
+        Some(path) => Some(BundleOptions { path, attachments }),
+        None if !attachments.is_empty() => {
+            eprintln!("--attach requires --bundle");
+            exit(1);
+        }
+        None => None,
+    };
+
+    if list {
+        print_processes(json, include_helpers, &search_roots);
+        exit(0);
+    }
+
+    if hub_projects {
+        print_hub_projects(json, &search_roots);
+        exit(0);
+    }
+
+    if let Some(project) = offline {
+        let project_path = if Path::new(&project).is_dir() {
+            PathBuf::from(&project)
+        } else {
+            let projects = unity_hub_projects();
+            let found = projects.into_iter().find(|x| {
+                let folder_name = x.path().file_name().and_then(|x| x.to_str());
+                x.title().is_some_and(|x| x.eq_ignore_ascii_case(&project))
+                    || folder_name.is_some_and(|x| x.eq_ignore_ascii_case(&project))
+            });
+            let Some(found) = found else {
+                eprintln!("No project found for {project}");
+                exit(1);
+            };
+            found.path().to_owned()
+        };
+        warn_weakened_rules(&settings);
+        let passphrase = encrypt.then(|| read_passphrase(passphrase));
+        match run_offline_console_log_saver(&project_path, &settings) {
+            Ok(log) => output_log(&log, passphrase, bundle.as_ref(), &settings),
+            Err(err) => {
+                eprintln!("failed to save log of {}: {err}", project_path.display());
+                exit(1);
+            }
+        }
+        exit(0);
+    }
+
+    if let Some(project) = project {
+        if pid.is_some() {
+            eprintln!("--project cannot be used with pid");
+            exit(1);
+        }
+        let unity_processes = find_unity_processes_in(&search_roots);
+        let matched = unity_processes
+            .iter()
+            .filter(|x| x.is_for_project(&project))
+            .collect::<Vec<_>>();
+        match matched.as_slice() {
+            [] => {
+                eprintln!("No unity processes found for {project}");
+                exit(1);
+            }
+            [process] => pid = Some(process.pid()),
+            _ => {
+                eprintln!("Multiple Unity Editors found for {project}:");
+                for process in matched {
+                    eprintln!("{} for {}", process.pid(), process.project_path().display());
+                }
+                exit(1);
+            }
+        }
+    }
+
+    if all {
+        if pid.is_some() || watch.is_some() || bundle.is_some() {
+            eprintln!("--all cannot be used with pid, --watch or --bundle");
+            exit(1);
+        }
+        warn_weakened_rules(&settings);
+        let passphrase = encrypt.then(|| read_passphrase(passphrase));
+        all_main(&settings, &output_dir, &search_roots, passphrase);
+    }
+
     if pid.is_none() {
-        let unity_processes = find_unity_processes();
+        let unity_processes = find_unity_processes_in(&search_roots);
         if unity_processes.is_empty() {
             eprintln!("No unity processes found");
             exit(1);
@@ -73,20 +302,655 @@ fn main() {
     }
 
     let pid = pid.unwrap();
+    warn_weakened_rules(&settings);
+    // ask passphrase before capturing since capturing takes some time
+    let passphrase = encrypt.then(|| read_passphrase(passphrase));
+
+    if let Some(interval) = watch {
+        if bundle.is_some() {
+            eprintln!("--watch cannot be used with --bundle");
+            exit(1);
+        }
+        let Some(process) = find_unity_processes_in(&search_roots)
+            .into_iter()
+            .find(|x| x.pid() == pid)
+        else {
+            eprintln!("Unity process {pid} not found");
+            exit(1);
+        };
+        watch_main(&process, &settings, interval, &watch_options, passphrase);
+    }
+
     match run_console_log_saver(pid, &settings) {
-        Ok(log) => print!("{log}"),
+        Ok(log) => output_log(&log, passphrase, bundle.as_ref(), &settings),
         Err(err) => eprintln!("failed to run console log: {err}"),
     }
 }
 
-fn print_processes() {
-    for process in find_unity_processes() {
-        eprintln!("{} for {}", process.pid(), process.project_path().display());
+struct BundleOptions {
+    path: PathBuf,
+    attachments: Vec<PathBuf>,
+}
+
+/// Prints the CLS file, or saves the bundle with the attachments if --bundle is specified.
+fn output_log(
+    log: &str,
+    passphrase: Option<String>,
+    bundle: Option<&BundleOptions>,
+    settings: &ConsoleLogSaverConfig,
+) {
+    let Some(bundle) = bundle else {
+        match passphrase {
+            Some(passphrase) => print!("{}", encrypt_cls_file(log, &passphrase)),
+            None => print!("{log}"),
+        }
+        return;
+    };
+
+    // the attachments are not encrypted since they may be binary
+    let log = match passphrase {
+        Some(passphrase) => encrypt_cls_file(log, &passphrase),
+        None => log.to_owned(),
+    };
+
+    let mut builder = ClsBundleBuilder::new(settings);
+    for path in &bundle.attachments {
+        if let Err(err) = builder.attach_file(path) {
+            eprintln!("failed to read {}: {err}", path.display());
+            exit(1);
+        }
+    }
+    let file = match std::fs::File::create(&bundle.path) {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("failed to create {}: {err}", bundle.path.display());
+            exit(1);
+        }
+    };
+    if let Err(err) = builder.write(&log, file) {
+        eprintln!("failed to write {}: {err}", bundle.path.display());
+        exit(1);
+    }
+}
+
+/// Validates the bundle and extracts the files into the directory.
+fn unpack_bundle_main(path: &str, directory: &Path) -> ! {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("failed to read {path}: {err}");
+            exit(1);
+        }
+    };
+    let bundle = match ClsBundle::read(file) {
+        Ok(bundle) => bundle,
+        Err(err) => {
+            eprintln!("invalid bundle {path}: {err}");
+            exit(1);
+        }
+    };
+    if let Err(err) = bundle.unpack(directory) {
+        eprintln!("failed to unpack {path}: {err}");
+        exit(1);
+    }
+    for attachment in bundle.attachments() {
+        let redaction = match attachment.redaction() {
+            AttachmentRedaction::Redacted => "redacted",
+            AttachmentRedaction::Unredacted => "not redacted",
+        };
+        eprintln!("attachments/{}: {redaction}", attachment.name());
+    }
+    exit(0);
+}
+
+/// Captures all editors and saves to `{project name}.txt` in the directory.
+fn all_main(
+    settings: &ConsoleLogSaverConfig,
+    directory: &Path,
+    search_roots: &[PathBuf],
+    passphrase: Option<String>,
+) -> ! {
+    let unity_processes = find_unity_processes_in(search_roots);
+    if unity_processes.is_empty() {
+        eprintln!("No unity processes found");
+        exit(1);
+    }
+    if let Err(err) = std::fs::create_dir_all(directory) {
+        eprintln!("failed to create {}: {err}", directory.display());
+        exit(1);
+    }
+
+    let mut used_names = std::collections::HashSet::new();
+    let mut results = Vec::new();
+    for process in &unity_processes {
+        let project_name = process
+            .project_path()
+            .file_name()
+            .map(|x| sanitize_file_name(&x.to_string_lossy()))
+            .filter(|x| !x.is_empty())
+            .unwrap_or_else(|| "project".to_owned());
+        let mut file_name = format!("{project_name}.txt");
+        if !used_names.insert(file_name.clone()) {
+            // multiple projects with the same name
+            file_name = format!("{project_name}-{}.txt", process.pid());
+            used_names.insert(file_name.clone());
+        }
+        let path = directory.join(file_name);
+
+        // lldb may panic on some failures, and we want to continue with other editors
+        let result = std::panic::catch_unwind(|| run_console_log_saver(process.pid(), settings));
+        let result = match result {
+            Ok(Ok(log)) => {
+                let log = match &passphrase {
+                    Some(passphrase) => encrypt_cls_file(&log, passphrase),
+                    None => log,
+                };
+                std::fs::write(&path, log)
+                    .map_err(|err| format!("failed to write {}: {err}", path.display()))
+            }
+            Ok(Err(err)) => Err(format!("failed to run console log: {err}")),
+            Err(_) => Err("failed to run console log: panicked".to_owned()),
+        };
+        results.push((process, path, result));
+    }
+
+    eprintln!();
+    let mut failed = false;
+    for (process, path, result) in results {
+        let project_path = process.project_path().display();
+        match result {
+            Ok(()) => eprintln!(
+                "OK     {} for {project_path}: {}",
+                process.pid(),
+                path.display()
+            ),
+            Err(err) => {
+                failed = true;
+                eprintln!("FAILED {} for {project_path}: {err}", process.pid());
+            }
+        }
+    }
+
+    exit(if failed { 1 } else { 0 })
+}
+
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+struct WatchOptions {
+    directory: PathBuf,
+    keep: usize,
+    snapshot_on_error: bool,
+}
+
+fn watch_main(
+    process: &UnityProcess,
+    settings: &ConsoleLogSaverConfig,
+    interval: Duration,
+    options: &WatchOptions,
+    passphrase: Option<String>,
+) -> ! {
+    if let Err(err) = std::fs::create_dir_all(&options.directory) {
+        eprintln!("failed to create {}: {err}", options.directory.display());
+        exit(1);
+    }
+
+    eprintln!(
+        "watching {} for {}",
+        process.pid(),
+        process.project_path().display()
+    );
+
+    let write = |kind: &str, log: &str| {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let path = options.directory.join(format!("{kind}-{timestamp}.txt"));
+        let log = match &passphrase {
+            Some(passphrase) => encrypt_cls_file(log, passphrase),
+            None => log.to_owned(),
+        };
+        match std::fs::write(&path, log) {
+            Ok(()) => eprintln!("saved {}", path.display()),
+            Err(err) => eprintln!("failed to write {}: {err}", path.display()),
+        }
+        remove_old_files(&options.directory, kind, options.keep);
+    };
+
+    watch_console_log_saver(process, settings, interval, |capture| {
+        let capture: WatchCapture = match capture {
+            Ok(capture) => capture,
+            Err(err) => {
+                eprintln!("failed to run console log: {err}");
+                return ControlFlow::Continue(());
+            }
+        };
+        if capture.console_cleared {
+            eprintln!("the console was cleared or the editor was restarted");
+        }
+        write("console-log", &capture.log);
+
+        if options.snapshot_on_error && capture.new_errors != 0 {
+            let mut settings = settings.clone();
+            settings.since = None;
+            match run_console_log_saver(capture.pid, &settings) {
+                Ok(log) => write("snapshot", &log),
+                Err(err) => eprintln!("failed to run console log: {err}"),
+            }
+        }
+        ControlFlow::Continue(())
+    });
+
+    exit(0)
+}
+
+/// Removes the oldest `{kind}-{timestamp}.txt` files to keep `keep` files.
+fn remove_old_files(directory: &Path, kind: &str, keep: usize) {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return;
+    };
+    let mut files = entries
+        .filter_map(|x| x.ok())
+        .filter_map(|x| {
+            let name = x.file_name().into_string().ok()?;
+            let timestamp = name
+                .strip_prefix(kind)?
+                .strip_prefix('-')?
+                .strip_suffix(".txt")?
+                .parse::<u128>()
+                .ok()?;
+            Some((timestamp, x.path()))
+        })
+        .collect::<Vec<_>>();
+    if files.len() <= keep {
+        return;
+    }
+    files.sort();
+    for (_, path) in &files[..files.len() - keep] {
+        std::fs::remove_file(path).ok();
+    }
+}
+
+fn redact_main(exe: &str, mut args: impl Iterator<Item = String>) -> ! {
+    let mut settings = ConsoleLogSaverConfig::default();
+    let mut input = None;
+    let mut output = None;
+    let mut encrypt = None;
+    let mut passphrase = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            arg if parse_config_option(arg, &mut args, &mut settings) => {}
+            "--help" | "-h" => print_help(exe, 0),
+            "--output" | "-o" => {
+                let Some(path) = args.next() else {
+                    eprintln!("No opeand found for --output");
+                    exit(1);
+                };
+                output = Some(path);
+            }
+            "--encrypt" => encrypt = Some(true),
+            "--no-encrypt" => encrypt = Some(false),
+            "--passphrase" => {
+                let Some(value) = args.next() else {
+                    eprintln!("No opeand found for --passphrase");
+                    exit(1);
+                };
+                passphrase = Some(value);
+            }
+            arg if arg.starts_with("-") => {
+                eprintln!("unknown option: {}", arg);
+                exit(1);
+            }
+            path => input = Some(path.to_owned()),
+        }
+    }
+
+    let Some(input) = input else {
+        eprintln!("No input file specified");
+        exit(1);
+    };
+
+    let mut cls_file = read_file(&input);
+    let encrypted = is_encrypted_cls_file(&cls_file);
+    // the encrypted file is encrypted again unless --no-encrypt is specified
+    let encrypt = encrypt.unwrap_or(encrypted);
+
+    let passphrase = (encrypt || encrypted).then(|| read_passphrase(passphrase));
+
+    if encrypted {
+        cls_file = match decrypt_cls_file(&cls_file, passphrase.as_deref().unwrap()) {
+            Ok(decrypted) => decrypted,
+            Err(err) => {
+                eprintln!("failed to decrypt {input}: {err}");
+                exit(1);
+            }
+        };
+    }
+
+    warn_weakened_rules(&settings);
+    let mut redacted = match redact_cls_file(&cls_file, &settings) {
+        Ok(redacted) => redacted,
+        Err(err) => {
+            eprintln!("failed to parse {input}: {err}");
+            exit(1);
+        }
+    };
+
+    if encrypt {
+        redacted = encrypt_cls_file(&redacted, passphrase.as_deref().unwrap());
+    }
+
+    match output {
+        Some(output) => {
+            if let Err(err) = std::fs::write(&output, redacted) {
+                eprintln!("failed to write {output}: {err}");
+                exit(1);
+            }
+        }
+        None => print!("{redacted}"),
+    }
+
+    exit(0);
+}
+
+/// Parses options for ConsoleLogSaverConfig. returns false if the option is not for config.
+fn parse_config_option(
+    arg: &str,
+    args: &mut impl Iterator<Item = String>,
+    settings: &mut ConsoleLogSaverConfig,
+) -> bool {
+    match arg {
+        "--hide-user-name" => settings.hide_user_name = true,
+        "--show-user-name" => settings.hide_user_name = false,
+        "--hide-user-home" => settings.hide_user_home = true,
+        "--show-user-home" => settings.hide_user_home = false,
+        "--hide-os-info" => settings.hide_os_info = true,
+        "--show-os-info" => settings.hide_os_info = false,
+        "--hide-aws-upload-signature" => settings.hide_aws_upload_signature = true,
+        "--show-aws-upload-signature" => settings.hide_aws_upload_signature = false,
+        "--hide-text" => {
+            let Some(text) = args.next() else {
+                eprintln!("No opeand found for --hide-text");
+                exit(1);
+            };
+            settings
+                .custom_rules
+                .push(RedactionRule::text("custom", &text));
+        }
+        "--hide-regex" => {
+            let pattern = parse_regex_operand("--hide-regex", args);
+            settings
+                .custom_rules
+                .push(RedactionRule::new("custom", pattern));
+        }
+        "--allow" => {
+            let Some(word) = args.next() else {
+                eprintln!("No opeand found for --allow");
+                exit(1);
+            };
+            settings.allowlist.push(word);
+        }
+        "--severity" => {
+            let Some(severities) = args.next() else {
+                eprintln!("No opeand found for --severity");
+                exit(1);
+            };
+            settings.include_log = false;
+            settings.include_warning = false;
+            settings.include_error = false;
+            for severity in severities.split(',') {
+                match severity.trim() {
+                    "log" | "info" => settings.include_log = true,
+                    "warning" => settings.include_warning = true,
+                    "error" => settings.include_error = true,
+                    severity => {
+                        eprintln!("Invalid severity: {severity}");
+                        exit(1);
+                    }
+                }
+            }
+        }
+        "--max-entries" => {
+            let Some(count) = args.next() else {
+                eprintln!("No opeand found for --max-entries");
+                exit(1);
+            };
+            let Some(count) = count.parse::<usize>().ok() else {
+                eprintln!("Invalid count: {count}");
+                exit(1);
+            };
+            settings.max_entries = Some(count);
+        }
+        "--include" => settings.include_regex = Some(parse_regex_operand("--include", args)),
+        "--exclude" => settings.exclude_regex = Some(parse_regex_operand("--exclude", args)),
+        "--deduplicate" => settings.deduplicate = true,
+        "--plugin-inventory" => settings.include_plugin_inventory = true,
+        "--assembly-graph" => settings.include_assembly_graph = true,
+        "--editor-log-tail" => {
+            let Some(kb) = args.next() else {
+                eprintln!("No opeand found for --editor-log-tail");
+                exit(1);
+            };
+            let Some(kb) = kb.parse::<u64>().ok() else {
+                eprintln!("Invalid size: {kb}");
+                exit(1);
+            };
+            settings.editor_log_tail_kb = Some(kb);
+        }
+        "--editor-prev-log" => settings.include_previous_editor_log = true,
+        "--player-log" => settings.include_player_log = true,
+        _ => return false,
+    }
+    true
+}
+
+fn parse_regex_operand(option: &str, args: &mut impl Iterator<Item = String>) -> regex::Regex {
+    let Some(pattern) = args.next() else {
+        eprintln!("No opeand found for {option}");
+        exit(1);
+    };
+    match regex::Regex::new(&pattern) {
+        Ok(pattern) => pattern,
+        Err(err) => {
+            eprintln!("Invalid regex: {err}");
+            exit(1);
+        }
+    }
+}
+
+/// Parses the cursor, or reads the Capture-Cursor header of the CLS file.
+fn parse_cursor(cursor_or_path: &str) -> CaptureCursor {
+    if let Ok(cursor) = cursor_or_path.parse::<CaptureCursor>() {
+        return cursor;
+    }
+    let content = read_file(cursor_or_path);
+    if is_encrypted_cls_file(&content) {
+        eprintln!("{cursor_or_path} is encrypted. please specify the Capture-Cursor value instead");
+        exit(1);
+    }
+    let cls_file = match ClsFile::parse(&content) {
+        Ok(cls_file) => cls_file,
+        Err(err) => {
+            eprintln!("failed to parse {cursor_or_path}: {err}");
+            exit(1);
+        }
+    };
+    let Some(cursor) = CaptureCursor::from_cls_file(&cls_file) else {
+        eprintln!("{cursor_or_path} does not have Capture-Cursor header");
+        exit(1);
+    };
+    cursor
+}
+
+fn read_file(path: &str) -> String {
+    match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("failed to read {path}: {err}");
+            exit(1);
+        }
+    }
+}
+
+/// Returns the passphrase specified with --passphrase, CONSOLE_LOG_SAVER_PASSPHRASE
+/// environment variable or read from stdin.
+fn read_passphrase(passphrase: Option<String>) -> String {
+    if let Some(passphrase) = passphrase {
+        return passphrase;
+    }
+    if let Ok(passphrase) = std::env::var("CONSOLE_LOG_SAVER_PASSPHRASE") {
+        return passphrase;
+    }
+    let line = if std::io::stdin().is_terminal() {
+        // do not echo the passphrase
+        rpassword::prompt_password("Passphrase: ")
+    } else {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line).map(|_| line)
+    };
+    let Ok(line) = line else {
+        eprintln!("failed to read passphrase");
+        exit(1);
+    };
+    let passphrase = line.trim_end_matches(['\r', '\n']);
+    if passphrase.is_empty() {
+        eprintln!("passphrase is empty");
+        exit(1);
+    }
+    passphrase.to_owned()
+}
+
+fn warn_weakened_rules(settings: &ConsoleLogSaverConfig) {
+    for name in settings.weakened_rules() {
+        eprintln!(
+            "WARNING: the text for {name} is short so it will be hidden only as a whole word"
+        );
+    }
+}
+
+fn print_processes(json: bool, include_helpers: bool, search_roots: &[PathBuf]) {
+    let unity_processes = if include_helpers {
+        let kinds = [
+            UnityProcessKind::Editor,
+            UnityProcessKind::AssetImportWorker,
+            UnityProcessKind::ShaderCompiler,
+            UnityProcessKind::PackageManager,
+            UnityProcessKind::LicensingClient,
+            UnityProcessKind::CrashHandler,
+            UnityProcessKind::Player,
+        ];
+        find_unity_processes_of_kinds(search_roots, &kinds)
+    } else {
+        let kinds = [UnityProcessKind::Editor, UnityProcessKind::Player];
+        find_unity_processes_of_kinds(search_roots, &kinds)
+    };
+    let start_time = |process: &UnityProcess| {
+        process
+            .start_time()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|x| x.as_secs())
+            .unwrap_or(0)
+    };
+    if json {
+        let processes = unity_processes
+            .iter()
+            .map(|process| {
+                serde_json::json!({
+                    "pid": process.pid(),
+                    "kind": format!("{:?}", process.kind()),
+                    "projectPath": process.project_path().to_string_lossy(),
+                    "projectName": process.project_name(),
+                    "displayName": process.display_name(),
+                    "editorVersion": process.editor_version(),
+                    "executablePath": process.exe_path().to_string_lossy(),
+                    "startTime": start_time(process),
+                    "batchMode": process.is_batch_mode(),
+                })
+            })
+            .collect::<Vec<_>>();
+        println!("{}", serde_json::Value::Array(processes));
+    } else {
+        for process in unity_processes {
+            let mut flags = String::new();
+            if process.kind() != UnityProcessKind::Editor {
+                flags.push_str(&format!("\t({:?})", process.kind()));
+            }
+            if process.is_batch_mode() {
+                flags.push_str("\t(batchmode)");
+            }
+            // the start time is seconds since the unix epoch as in the JSON output
+            println!(
+                "{}\t{}\t{}\t{}\t{}\t{}{flags}",
+                process.pid(),
+                process.editor_version().unwrap_or("unknown"),
+                process.project_path().display(),
+                process.display_name().unwrap_or("unknown"),
+                process.exe_path().display(),
+                start_time(&process),
+            );
+        }
+    }
+}
+
+fn print_hub_projects(json: bool, search_roots: &[PathBuf]) {
+    let unity_processes = find_unity_processes_in(search_roots);
+    let editors = unity_hub_editors();
+    let projects = unity_hub_projects();
+
+    let running = |path: &Path| {
+        let path = path.to_string_lossy();
+        unity_processes
+            .iter()
+            .find(|x| x.is_for_project(&path))
+            .map(|x| x.pid())
+    };
+    let installed = |version: Option<&str>| {
+        version.is_some_and(|version| editors.iter().any(|x| x.version() == version))
+    };
+
+    if json {
+        let projects = projects
+            .iter()
+            .map(|project| {
+                serde_json::json!({
+                    "projectPath": project.path().to_string_lossy(),
+                    "title": project.title(),
+                    "editorVersion": project.editor_version(),
+                    "editorInstalled": installed(project.editor_version()),
+                    "pid": running(project.path()),
+                })
+            })
+            .collect::<Vec<_>>();
+        println!("{}", serde_json::Value::Array(projects));
+    } else {
+        for project in &projects {
+            let pid = running(project.path());
+            let mut flags = String::new();
+            if project.editor_version().is_some() && !installed(project.editor_version()) {
+                flags.push_str("\t(editor not installed)");
+            }
+            println!(
+                "{}\t{}\t{}{flags}",
+                pid.map_or("-".to_owned(), |x| x.to_string()),
+                project.editor_version().unwrap_or("unknown"),
+                project.path().display(),
+            );
+        }
     }
 }
 
 pub fn print_help(exe: &str, exit_code: i32) {
     eprintln!("{exe} [OPTIONS] <unity pid>");
+    eprintln!("{exe} redact [OPTIONS] <cls file>");
     eprintln!("ConsoleLogSaver {}", env!("CARGO_PKG_VERSION"));
     eprintln!("ConsoleLogSaver with lldb native debugger");
     eprintln!();
@@ -99,9 +963,64 @@ pub fn print_help(exe: &str, exit_code: i32) {
     eprintln!("\t--show-os-info: disable Hide OS Info flag");
     eprintln!("\t--hide-aws-upload-signature: enable Hide AWS Upload Signature flag");
     eprintln!("\t--show-aws-upload-signature: disable Hide AWS Upload Signature flag");
+    eprintln!("\t--hide-text <text>: hide the text in the log");
+    eprintln!("\t--hide-regex <regex>: hide the text matches the regex in the log");
+    eprintln!("\t--allow <word>: never hide the word even if some filter matches a part of it");
+    eprintln!("\t--severity <log,warning,error>: save only the entries with the severities");
+    eprintln!("\t--max-entries <count>: save only the newest entries up to the count");
+    eprintln!("\t--include <regex>: save only the entries matches the regex");
+    eprintln!("\t--exclude <regex>: do not save the entries matches the regex");
+    eprintln!("\t--deduplicate: save identical entries once with Repeat-Count");
+    eprintln!("\t--plugin-inventory: list native plugins, DLLs and asmdefs in Assets and packages");
+    eprintln!(
+        "\t--assembly-graph: save asmdefs and asmrefs with missing references and reference cycles"
+    );
+    eprintln!("\t--editor-log-tail <KB>: also save the last kilobytes of Editor.log");
+    eprintln!("\t--editor-prev-log: with --editor-log-tail, also save the last kilobytes of Editor-prev.log");
+    eprintln!("\t--player-log: also save Player.log of the player built from the project");
+    eprintln!("\t--since <cursor or cls file>: save only the entries logged after the capture");
+    eprintln!("\t    with the Capture-Cursor, or the capture saved to the file");
+    eprintln!("\t--watch <seconds>: capture new entries periodically until interrupted");
+    eprintln!("\t--watch-dir <dir>: the directory to save the captures of --watch. defaults to current directory");
+    eprintln!("\t--watch-keep <count>: the number of captures of --watch to keep. defaults to 20");
+    eprintln!("\t--snapshot-on-error: with --watch, also save all entries when new error appears");
+    eprintln!("\t--all: capture all unity editors and save to <project name>.txt for each project");
+    eprintln!("\t--output-dir <dir>: the directory to save the files of --all. defaults to current directory");
+    eprintln!(
+        "\t--bundle <file>: save the output and the attachments to the zip file instead of stdout"
+    );
+    eprintln!("\t--attach <file>: with --bundle, add the file like screenshots or crash dumps to the bundle.");
+    eprintln!("\t    the personal information in the text files is hidden but binary files are saved as is");
+    eprintln!(
+        "\t--unpack-bundle <file>: check the bundle and extract to the --output-dir, and exit"
+    );
+    eprintln!("\t--encrypt: encrypt the output with passphrase. with --bundle, only console-log.txt is encrypted");
+    eprintln!("\t--decrypt <file>: decrypt the encrypted file and exit");
+    eprintln!("\t--passphrase <passphrase>: the passphrase for --encrypt and --decrypt.");
+    eprintln!(
+        "\t    CONSOLE_LOG_SAVER_PASSPHRASE environment variable or stdin is used if not specified"
+    );
     eprintln!("\t--pid <pid>: specify pid of unity");
-    eprintln!("\t--list: list unity processes and exit");
+    eprintln!(
+        "\t--project <path or name>: specify unity by the project path or the project folder name"
+    );
+    eprintln!("\t--search-root <dir>: find unity opened for the projects in the directory");
+    eprintln!(
+        "\t    with Library/EditorInstance.json even if the command line has no project path"
+    );
+    eprintln!("\t--list: list unity editors and players to stdout and exit. each line has pid, version, project path, display name, executable path and start time");
+    eprintln!("\t--json: with --list, print the list as JSON");
+    eprintln!(
+        "\t--include-helpers: with --list, also list asset import workers, shader compilers, etc."
+    );
+    eprintln!("\t--hub-projects: list the projects in Unity Hub with pid if running, and exit");
+    eprintln!("\t--offline <path or name>: save Editor.log of the project not opened now");
     eprintln!("\t--help: show this message and exit");
+    eprintln!();
+    eprintln!("REDACT OPTIONS:");
+    eprintln!("\t--output <file>: write the redacted file to the file instead of stdout");
+    eprintln!("\t--encrypt: encrypt the output. the output is encrypted if the input is encrypted");
+    eprintln!("\t--no-encrypt: do not encrypt the output even if the input is encrypted");
 
     exit(exit_code);
 }
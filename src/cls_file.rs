@@ -1,9 +1,13 @@
+use crate::encryption::{decrypt_cls_file, is_encrypted_cls_file, DecryptError};
+use std::borrow::Cow;
+
 pub struct ClsFileBuilder {
     building: String,
     separator: String,
 }
 
 impl ClsFileBuilder {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new() -> ClsHeadingBuilder {
         let mut building = String::new();
         building.push_str("ConsoleLogSaverData/1.0\n");
@@ -13,7 +17,7 @@ impl ClsFileBuilder {
         );
         building.push_str("Separator: ");
         building.push_str(&separator);
-        building.push_str("\n");
+        building.push('\n');
         ClsHeadingBuilder {
             builder: ClsFileBuilder {
                 building,
@@ -28,21 +32,21 @@ impl ClsFileBuilder {
         }
 
         check_header_name(name);
-        check_header_value(name);
+        check_header_value(value);
 
         self.building.push_str(name);
         self.building.push_str(": ");
         self.building.push_str(value);
-        self.building.push_str("\n");
+        self.building.push('\n');
     }
 
     fn end_of_heading(&mut self) {
-        self.building.push_str("\n");
+        self.building.push('\n');
     }
 
     fn end_of_section(&mut self) {
         self.building.push_str(self.separator.as_str());
-        self.building.push_str("\n");
+        self.building.push('\n');
     }
 }
 
@@ -92,6 +96,15 @@ impl ClsBodyBuilder {
         self
     }
 
+    /// Ends the section without `Content` field, like the sections in the files by the old C# ConsoleLogSaver.
+    pub fn add_untyped_content(&mut self, content: &str) -> &mut Self {
+        self.builder.end_of_heading();
+        self.builder.building.push_str(content);
+        self.builder.end_of_section();
+        self.has_content = false;
+        self
+    }
+
     pub fn build(mut self) -> String {
         if self.has_content {
             self.builder.end_of_heading();
@@ -102,18 +115,204 @@ impl ClsBodyBuilder {
 }
 
 fn check_header_name(name: &str) {
-    if name.len() == 0 {
+    if name.is_empty() {
         panic!("header name is empty")
     }
-    if !name.bytes().all(|c| matches!(c, b'0'..=b'9' | b'A'..=b'Z' | b'a'..=b'z' | b'!' | b'#'
-        | b'$' | b'%' | b'&' | b'\'' | b'*' | b'+' | b'-' | b'.' | b'^' | b'_' | b'`' | b'|' | b'~'
-    )) {
+    if !is_valid_header_name(name) {
         panic!("header name contains invalid characters")
     }
 }
 
+fn is_valid_header_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.bytes().all(|c| matches!(c, b'0'..=b'9' | b'A'..=b'Z' | b'a'..=b'z' | b'!' | b'#'
+            | b'$' | b'%' | b'&' | b'\'' | b'*' | b'+' | b'-' | b'.' | b'^' | b'_' | b'`' | b'|' | b'~'
+        ))
+}
+
 fn check_header_value(value: &str) {
-    if value.contains('\r') || value.contains('\n') {
+    if !is_valid_header_value(value) {
         panic!("header value contains newline")
     }
 }
+
+pub(crate) fn is_valid_header_value(value: &str) -> bool {
+    !value.contains(['\r', '\n'])
+}
+
+/// Replaces the line breaks with spaces for the header values read from files or the editor.
+pub(crate) fn single_line(value: &str) -> Cow<'_, str> {
+    if is_valid_header_value(value) {
+        Cow::Borrowed(value)
+    } else {
+        Cow::Owned(value.replace("\r\n", " ").replace(['\r', '\n'], " "))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClsParseError {
+    #[error("unsupported version")]
+    UnsupportedVersion,
+    #[error("invalid field at line {line}")]
+    InvalidField { line: usize },
+    #[error("separator not found")]
+    SeparatorNotFound,
+    #[error("the file is encrypted")]
+    Encrypted,
+    #[error(transparent)]
+    Decrypt(#[from] DecryptError),
+}
+
+/// The parsed CLS file.
+///
+/// This parser accepts files created by the old C# ConsoleLogSaver as well, which may use CRLF.
+/// The line endings in the contents are kept as is.
+#[derive(Debug, Clone)]
+pub struct ClsFile {
+    headers: Vec<(String, String)>,
+    sections: Vec<ClsSection>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClsSection {
+    fields: Vec<(String, String)>,
+    content: String,
+}
+
+impl ClsFile {
+    pub fn parse(text: &str) -> Result<ClsFile, ClsParseError> {
+        if is_encrypted_cls_file(text) {
+            return Err(ClsParseError::Encrypted);
+        }
+
+        let lines = text.split('\n').collect::<Vec<_>>();
+
+        if !lines[0].starts_with("ConsoleLogSaverData/1.") {
+            return Err(ClsParseError::UnsupportedVersion);
+        }
+
+        let header_end = lines
+            .iter()
+            .skip(1)
+            .position(|x| trim_cr(x).is_empty())
+            .map(|x| x + 1)
+            .unwrap_or(lines.len());
+        let headers = parse_fields(&lines[1..header_end], 1)?;
+
+        let separator = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("separator"))
+            .map(|(_, value)| value.as_str())
+            .ok_or(ClsParseError::SeparatorNotFound)?;
+
+        let mut sections = vec![];
+
+        let Some(mut line_index) =
+            (header_end..lines.len()).find(|&i| trim_cr(lines[i]).ends_with(separator))
+        else {
+            return Ok(ClsFile { headers, sections });
+        };
+        line_index += 1;
+
+        while line_index < lines.len() {
+            let section_start = line_index;
+            while line_index < lines.len() && !trim_cr(lines[line_index]).ends_with(separator) {
+                line_index += 1;
+            }
+            if line_index < lines.len() {
+                line_index += 1;
+            }
+            let mut section_lines = lines[section_start..line_index].to_vec();
+            if section_lines.iter().all(|x| trim_cr(x).is_empty()) {
+                break;
+            }
+            if let Some(last) = section_lines.last_mut() {
+                *last = trim_cr(last);
+            }
+            let section = section_lines.join("\n");
+            let section = section.strip_suffix(separator).unwrap_or(&section);
+
+            let section_lines = section.split('\n').collect::<Vec<_>>();
+            let fields_end = section_lines
+                .iter()
+                .position(|x| trim_cr(x).is_empty())
+                .unwrap_or(section_lines.len());
+            let fields = parse_fields(&section_lines[..fields_end], section_start)?;
+            let content = section_lines
+                .get(fields_end + 1..)
+                .unwrap_or_default()
+                .join("\n");
+
+            sections.push(ClsSection { fields, content });
+        }
+
+        Ok(ClsFile { headers, sections })
+    }
+
+    /// Parses the file, decrypting with the passphrase if the file is encrypted.
+    pub fn parse_with_passphrase(text: &str, passphrase: &str) -> Result<ClsFile, ClsParseError> {
+        if is_encrypted_cls_file(text) {
+            Self::parse(&decrypt_cls_file(text, passphrase)?)
+        } else {
+            Self::parse(text)
+        }
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_field(&self.headers, name)
+    }
+
+    pub fn sections(&self) -> &[ClsSection] {
+        &self.sections
+    }
+}
+
+impl ClsSection {
+    pub fn fields(&self) -> &[(String, String)] {
+        &self.fields
+    }
+
+    pub fn field(&self, name: &str) -> Option<&str> {
+        find_field(&self.fields, name)
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.field("content")
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+fn trim_cr(line: &str) -> &str {
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+fn find_field<'a>(fields: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(x, _)| x.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+// first_line is 0-based index of the first line in the file
+fn parse_fields(lines: &[&str], first_line: usize) -> Result<Vec<(String, String)>, ClsParseError> {
+    let mut fields = vec![];
+    for (i, line) in lines.iter().enumerate() {
+        let invalid = || ClsParseError::InvalidField {
+            line: first_line + i + 1,
+        };
+        let (name, value) = trim_cr(line).split_once(':').ok_or_else(invalid)?;
+        if !is_valid_header_name(name) {
+            return Err(invalid());
+        }
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        fields.push((name.to_owned(), value.to_owned()));
+    }
+    Ok(fields)
+}
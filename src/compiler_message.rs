@@ -0,0 +1,121 @@
+use crate::cls_file::{single_line, ClsBodyBuilder};
+use crate::log_filter::{LogEntry, MODE_SCRIPT_COMPILE_ERROR, MODE_SCRIPT_COMPILE_WARNING};
+use crate::{ConsoleLogSaverConfig, ReplaceSet, Result, TransferDataReader};
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::LazyLock;
+
+// see UnityEditor.Compilation.CompilerMessageType
+const COMPILER_MESSAGE_ERROR: i32 = 0;
+const COMPILER_MESSAGE_WARNING: i32 = 1;
+
+/// The message of the last script compilation.
+pub(crate) struct CompilerMessage {
+    assembly: String,
+    file: String,
+    line: i32,
+    column: i32,
+    message_type: i32,
+    message: String,
+}
+
+impl CompilerMessage {
+    /// Reads the compiler messages transferred from cls-attach-lib.
+    /// Returns None if the editor does not support getting compiler messages.
+    pub fn read_all(reader: &mut TransferDataReader) -> Result<Option<Vec<CompilerMessage>>> {
+        let count = reader.read_i32()?;
+        if count < 0 {
+            return Ok(None);
+        }
+        let mut messages = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            messages.push(CompilerMessage {
+                assembly: reader.read_string()?,
+                file: reader.read_string()?,
+                line: reader.read_i32()?,
+                column: reader.read_i32()?,
+                message_type: reader.read_i32()?,
+                message: reader.read_string()?,
+            });
+        }
+        Ok(Some(messages))
+    }
+
+    /// Creates the message from the console entry of the script compilation
+    /// like `Assets/Foo.cs(10,5): error CS1002: ; expected`.
+    /// The assembly is not known in this case.
+    pub fn from_log_entry(entry: &LogEntry) -> Option<CompilerMessage> {
+        static COMPILER_OUTPUT: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"^(?<file>.+?)\((?<line>\d+),(?<column>\d+)\): (?:error|warning|info) ")
+                .unwrap()
+        });
+
+        let message_type = if entry.mode & MODE_SCRIPT_COMPILE_ERROR != 0 {
+            COMPILER_MESSAGE_ERROR
+        } else if entry.mode & MODE_SCRIPT_COMPILE_WARNING != 0 {
+            COMPILER_MESSAGE_WARNING
+        } else {
+            return None;
+        };
+        let captures = COMPILER_OUTPUT.captures(&entry.message);
+        let (file, line, column) = match &captures {
+            Some(captures) => (
+                captures["file"].to_owned(),
+                captures["line"].parse().unwrap_or(0),
+                captures["column"].parse().unwrap_or(0),
+            ),
+            None => (String::new(), 0, 0),
+        };
+
+        Some(CompilerMessage {
+            assembly: String::new(),
+            file,
+            line,
+            column,
+            message_type,
+            message: entry.message.clone(),
+        })
+    }
+
+    fn severity(&self) -> &'static str {
+        match self.message_type {
+            COMPILER_MESSAGE_ERROR => "error",
+            COMPILER_MESSAGE_WARNING => "warning",
+            _ => "info",
+        }
+    }
+}
+
+/// Appends `compiler-message` sections for the messages with the severities in the config.
+pub(crate) fn append_compiler_messages(
+    builder: &mut ClsBodyBuilder,
+    messages: &[CompilerMessage],
+    config: &ConsoleLogSaverConfig,
+    replacer: &ReplaceSet,
+) {
+    for message in messages {
+        let included = match message.severity() {
+            "error" => config.include_error,
+            "warning" => config.include_warning,
+            _ => config.include_log,
+        };
+        if !included {
+            continue;
+        }
+
+        if !message.assembly.is_empty() {
+            builder.add_header("Assembly", &single_line(&message.assembly));
+        }
+        if !message.file.is_empty() {
+            let file = replacer.replace_all(Cow::Borrowed(&message.file));
+            builder.add_header("File", &single_line(&file));
+            builder.add_header("Line", &message.line.to_string());
+            builder.add_header("Column", &message.column.to_string());
+        }
+        builder.add_header("Severity", message.severity());
+        builder.add_content(
+            "compiler-message",
+            &replacer.replace_all(Cow::Borrowed(&message.message)),
+        );
+    }
+}
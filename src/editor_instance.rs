@@ -0,0 +1,48 @@
+use crate::ProcessId;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// The `Library/EditorInstance.json` the editor writes while the project is open.
+#[derive(Deserialize)]
+pub(crate) struct EditorInstance {
+    pub process_id: ProcessId,
+    pub version: Option<String>,
+}
+
+pub(crate) fn read_editor_instance(project_path: &Path) -> Option<EditorInstance> {
+    let json = std::fs::read_to_string(project_path.join("Library/EditorInstance.json")).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Reads `EditorInstance.json` of the candidate projects and returns the map from the pid.
+///
+/// The candidates are the search roots, direct children of the search roots,
+/// and the projects listed in Unity Hub.
+pub(crate) fn find_editor_instances(
+    search_roots: &[PathBuf],
+) -> HashMap<ProcessId, (PathBuf, EditorInstance)> {
+    let mut candidates = Vec::new();
+    for root in search_roots {
+        candidates.push(root.clone());
+        if let Ok(entries) = std::fs::read_dir(root) {
+            candidates.extend(
+                entries
+                    .filter_map(|x| x.ok())
+                    .filter(|x| x.file_type().map(|x| x.is_dir()).unwrap_or(false))
+                    .map(|x| x.path()),
+            );
+        }
+    }
+    candidates.extend(crate::unity_hub::unity_hub_project_paths());
+
+    let mut instances = HashMap::new();
+    for project_path in candidates {
+        if let Some(instance) = read_editor_instance(&project_path) {
+            instances
+                .entry(instance.process_id)
+                .or_insert((project_path, instance));
+        }
+    }
+    instances
+}
@@ -0,0 +1,124 @@
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// The directory the editor writes `Editor.log` and `Editor-prev.log` to.
+fn editor_log_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let dir = PathBuf::from(std::env::var_os("LOCALAPPDATA")?).join("Unity/Editor");
+    #[cfg(target_os = "macos")]
+    let dir = home::home_dir()?.join("Library/Logs/Unity");
+    #[cfg(target_os = "linux")]
+    let dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| Some(home::home_dir()?.join(".config")))?
+        .join("unity3d");
+    Some(dir)
+}
+
+/// The directory the player built with the company and product name writes `Player.log` to.
+fn player_log_dir(company_name: &str, product_name: &str) -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let dir = PathBuf::from(std::env::var_os("USERPROFILE")?).join("AppData/LocalLow");
+    #[cfg(target_os = "macos")]
+    let dir = home::home_dir()?.join("Library/Logs");
+    #[cfg(target_os = "linux")]
+    let dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| Some(home::home_dir()?.join(".config")))?
+        .join("unity3d");
+    Some(dir.join(company_name).join(product_name))
+}
+
+/// Finds `Player.log` of the player built from the project,
+/// with `companyName` and `productName` in `ProjectSettings/ProjectSettings.asset`.
+pub(crate) fn player_log_path(project_path: &Path) -> Option<PathBuf> {
+    let path = project_path.join("ProjectSettings/ProjectSettings.asset");
+    let content = std::fs::read_to_string(path).ok()?;
+    let company_name = crate::find_yaml_value(&content, "companyName")?;
+    let company_name = crate::unquote_yaml_value(company_name);
+    let product_name = crate::find_yaml_value(&content, "productName")?;
+    let product_name = crate::unquote_yaml_value(product_name);
+    let path = player_log_dir(&company_name, &product_name)?.join("Player.log");
+    path.is_file().then_some(path)
+}
+
+/// Returns `Editor.log` and `Editor-prev.log`, newer first.
+pub(crate) fn editor_log_paths() -> Vec<PathBuf> {
+    let Some(dir) = editor_log_dir() else {
+        return vec![];
+    };
+    vec![dir.join("Editor.log"), dir.join("Editor-prev.log")]
+}
+
+/// The size of the log to read if not specified, since the log can be hundreds of megabytes.
+pub(crate) const DEFAULT_MAX_LOG_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Finds the editor log written by the editor for the project,
+/// and reads the last `max_bytes` bytes of it like [`read_log_tail`].
+///
+/// The editor writes the project path near the beginning of the log,
+/// as the argument after `-projectPath` and as `Successfully changed project path to: <path>`.
+pub(crate) fn read_editor_log_for_project(
+    project_path: &Path,
+    max_bytes: u64,
+) -> Option<(PathBuf, String, bool)> {
+    let normalize = |path: &str| {
+        let path = path.trim().trim_end_matches(['/', '\\']);
+        if cfg!(windows) {
+            path.replace('\\', "/").to_lowercase()
+        } else {
+            path.to_owned()
+        }
+    };
+    let project_path = normalize(&project_path.to_string_lossy());
+
+    for path in editor_log_paths() {
+        let Ok(file) = std::fs::File::open(&path) else {
+            continue;
+        };
+        // the project is loaded in the first part of the log
+        let mut head = Vec::new();
+        if file.take(64 * 1024).read_to_end(&mut head).is_err() {
+            continue;
+        }
+        let head = String::from_utf8_lossy(&head);
+        let lines = head.lines().take(200).collect::<Vec<_>>();
+        let matches = lines.iter().enumerate().any(|(index, line)| {
+            let line = line.trim();
+            let loaded = if line.eq_ignore_ascii_case("-projectPath") {
+                lines.get(index + 1).copied()
+            } else {
+                line.strip_prefix("Successfully changed project path to:")
+            };
+            loaded.is_some_and(|loaded| normalize(loaded) == project_path)
+        });
+
+        if matches {
+            let (content, truncated) = read_log_tail(&path, max_bytes)?;
+            return Some((path, content, truncated));
+        }
+    }
+
+    None
+}
+
+/// Reads the last `max_bytes` bytes of the log, from the beginning of a line.
+/// Returns the content and whether the beginning of the log is omitted.
+pub(crate) fn read_log_tail(path: &Path, max_bytes: u64) -> Option<(String, bool)> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let length = file.metadata().ok()?.len();
+    let truncated = length > max_bytes;
+    if truncated {
+        file.seek(SeekFrom::Start(length - max_bytes)).ok()?;
+    }
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).ok()?;
+
+    let mut content = String::from_utf8_lossy(&bytes).into_owned();
+    if truncated {
+        // the first line is cut in the middle
+        let first_line_end = content.find('\n').map_or(content.len(), |x| x + 1);
+        content.drain(..first_line_end);
+    }
+    Some((content, truncated))
+}
@@ -0,0 +1,132 @@
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use base64::Engine;
+
+// The format is designed to be decryptable with WebCrypto in the viewer.
+//
+// ConsoleLogSaverEncrypted/1.0
+// Encryption: AES-256-GCM
+// Key-Derivation: PBKDF2-HMAC-SHA256
+// Iterations: <iterations>
+// Salt: <base64>
+// Nonce: <base64>
+//
+// <base64 encoded cipher text with tag, wrapped>
+
+const MAGIC: &str = "ConsoleLogSaverEncrypted/1.";
+const ENCRYPTION: &str = "AES-256-GCM";
+const KEY_DERIVATION: &str = "PBKDF2-HMAC-SHA256";
+const ITERATIONS: u32 = 600_000;
+/// Files with more iterations are rejected since a crafted file can make decryption take forever.
+const MAX_ITERATIONS: u32 = 10_000_000;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DecryptError {
+    #[error("not an encrypted file")]
+    NotEncrypted,
+    #[error("unsupported encryption: {0}")]
+    UnsupportedEncryption(String),
+    #[error("corrupted encrypted file")]
+    Corrupted,
+    #[error("too many iterations: {0}")]
+    TooManyIterations(u32),
+    #[error("wrong passphrase or corrupted file")]
+    WrongPassphrase,
+}
+
+/// Returns true if the text is an encrypted CLS file.
+pub fn is_encrypted_cls_file(text: &str) -> bool {
+    text.starts_with(MAGIC)
+}
+
+/// Encrypts the CLS file with the passphrase.
+pub fn encrypt_cls_file(cls_file: &str, passphrase: &str) -> String {
+    let mut salt = [0u8; SALT_LENGTH];
+    let mut nonce = [0u8; NONCE_LENGTH];
+    getrandom::getrandom(&mut salt).expect("failed to generate salt");
+    getrandom::getrandom(&mut nonce).expect("failed to generate nonce");
+
+    let cipher = create_cipher(passphrase, &salt, ITERATIONS);
+    let encrypted = cipher
+        .encrypt(Nonce::from_slice(&nonce), cls_file.as_bytes())
+        .expect("failed to encrypt");
+
+    let base64 = base64::engine::general_purpose::STANDARD;
+
+    let mut result = String::new();
+    result.push_str(MAGIC);
+    result.push_str("0\n");
+    result.push_str(&format!("Encryption: {ENCRYPTION}\n"));
+    result.push_str(&format!("Key-Derivation: {KEY_DERIVATION}\n"));
+    result.push_str(&format!("Iterations: {ITERATIONS}\n"));
+    result.push_str(&format!("Salt: {}\n", base64.encode(salt)));
+    result.push_str(&format!("Nonce: {}\n", base64.encode(nonce)));
+    result.push('\n');
+    let encoded = base64.encode(encrypted);
+    for line in encoded.as_bytes().chunks(76) {
+        result.push_str(std::str::from_utf8(line).unwrap());
+        result.push('\n');
+    }
+    result
+}
+
+/// Decrypts the CLS file encrypted with [`encrypt_cls_file`].
+pub fn decrypt_cls_file(encrypted: &str, passphrase: &str) -> Result<String, DecryptError> {
+    let mut lines = encrypted.lines();
+    if !lines.next().is_some_and(is_encrypted_cls_file) {
+        return Err(DecryptError::NotEncrypted);
+    }
+
+    let base64 = base64::engine::general_purpose::STANDARD;
+
+    let mut iterations = None;
+    let mut salt = None;
+    let mut nonce = None;
+    for line in lines.by_ref() {
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(':').ok_or(DecryptError::Corrupted)?;
+        let value = value.trim();
+        match name.to_ascii_lowercase().as_str() {
+            "encryption" if value != ENCRYPTION => {
+                return Err(DecryptError::UnsupportedEncryption(value.to_owned()));
+            }
+            "key-derivation" if value != KEY_DERIVATION => {
+                return Err(DecryptError::UnsupportedEncryption(value.to_owned()));
+            }
+            "iterations" => iterations = value.parse::<u32>().ok(),
+            "salt" => salt = base64.decode(value).ok(),
+            "nonce" => nonce = base64.decode(value).ok(),
+            _ => {}
+        }
+    }
+
+    let iterations = iterations.ok_or(DecryptError::Corrupted)?;
+    if iterations > MAX_ITERATIONS {
+        return Err(DecryptError::TooManyIterations(iterations));
+    }
+    let salt = salt.ok_or(DecryptError::Corrupted)?;
+    let nonce = nonce.ok_or(DecryptError::Corrupted)?;
+    if nonce.len() != NONCE_LENGTH {
+        return Err(DecryptError::Corrupted);
+    }
+
+    let body = lines.map(str::trim).collect::<String>();
+    let body = base64.decode(body).map_err(|_| DecryptError::Corrupted)?;
+
+    let cipher = create_cipher(passphrase, &salt, iterations);
+    let decrypted = cipher
+        .decrypt(Nonce::from_slice(&nonce), body.as_slice())
+        .map_err(|_| DecryptError::WrongPassphrase)?;
+
+    String::from_utf8(decrypted).map_err(|_| DecryptError::Corrupted)
+}
+
+fn create_cipher(passphrase: &str, salt: &[u8], iterations: u32) -> Aes256Gcm {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    Aes256Gcm::new(&key.into())
+}
@@ -0,0 +1,250 @@
+use sha1::{Digest, Sha1};
+use std::path::{Path, PathBuf};
+
+/// The state of the git working tree, read from `.git` directly.
+pub(crate) struct GitState {
+    /// The commit of HEAD. None for the repository without commits.
+    pub commit: Option<String>,
+    /// The branch of HEAD. None for detached HEAD.
+    pub branch: Option<String>,
+    /// Whether tracked files are modified. None if the index cannot be read.
+    pub dirty: Option<bool>,
+}
+
+/// Reads the state of the working tree. Returns None if it's not a git working tree.
+pub(crate) fn read_git_state(work_tree: &Path) -> Option<GitState> {
+    let git_dir = find_git_dir(work_tree)?;
+    // worktrees have refs in the common dir
+    let common_dir = match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common_dir) => git_dir.join(common_dir.trim()),
+        Err(_) => git_dir.clone(),
+    };
+
+    let head = std::fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    let (commit, branch) = match head.strip_prefix("ref:") {
+        Some(reference) => {
+            let reference = reference.trim();
+            let commit = resolve_ref(&git_dir, &common_dir, reference);
+            let branch = reference.strip_prefix("refs/heads/").unwrap_or(reference);
+            (commit, Some(branch.to_owned()))
+        }
+        None => (Some(head.to_owned()), None),
+    };
+
+    let dirty = if is_sha1_repository(&common_dir) {
+        is_dirty(work_tree, &git_dir.join("index"))
+    } else {
+        None
+    };
+
+    Some(GitState {
+        commit,
+        branch,
+        dirty,
+    })
+}
+
+/// Finds `.git` directory, or the directory specified with `gitdir:` in `.git` file.
+fn find_git_dir(work_tree: &Path) -> Option<PathBuf> {
+    let dot_git = work_tree.join(".git");
+    let metadata = std::fs::metadata(&dot_git).ok()?;
+    if metadata.is_dir() {
+        return Some(dot_git);
+    }
+    // submodules and worktrees have `.git` file
+    let content = std::fs::read_to_string(&dot_git).ok()?;
+    let git_dir = content.trim().strip_prefix("gitdir:")?.trim();
+    Some(work_tree.join(git_dir))
+}
+
+fn resolve_ref(git_dir: &Path, common_dir: &Path, reference: &str) -> Option<String> {
+    let mut reference = reference.to_owned();
+    // symbolic refs may be nested
+    for _ in 0..5 {
+        let loose = std::fs::read_to_string(git_dir.join(&reference))
+            .or_else(|_| std::fs::read_to_string(common_dir.join(&reference)));
+        let value = match loose {
+            Ok(value) => value.trim().to_owned(),
+            Err(_) => return find_packed_ref(common_dir, &reference),
+        };
+        match value.strip_prefix("ref:") {
+            Some(next) => reference = next.trim().to_owned(),
+            None => return Some(value),
+        }
+    }
+    None
+}
+
+fn find_packed_ref(common_dir: &Path, reference: &str) -> Option<String> {
+    let packed_refs = std::fs::read_to_string(common_dir.join("packed-refs")).ok()?;
+    packed_refs
+        .lines()
+        .filter(|x| !x.starts_with('#') && !x.starts_with('^'))
+        .filter_map(|x| x.split_once(' '))
+        .find(|(_, name)| *name == reference)
+        .map(|(commit, _)| commit.to_owned())
+}
+
+fn is_sha1_repository(common_dir: &Path) -> bool {
+    let Ok(config) = std::fs::read_to_string(common_dir.join("config")) else {
+        return true;
+    };
+    !config.lines().any(|line| {
+        let line = line.trim().to_ascii_lowercase();
+        line.starts_with("objectformat") && line.ends_with("sha256")
+    })
+}
+
+const MODE_TYPE_MASK: u32 = 0o170000;
+const MODE_SYMLINK: u32 = 0o120000;
+const MODE_GITLINK: u32 = 0o160000;
+const FLAG_EXTENDED: u16 = 0x4000;
+const FLAG_STAGE_MASK: u16 = 0x3000;
+const EXTENDED_FLAG_SKIP_WORKTREE: u16 = 0x4000;
+
+/// Compares the files in the index with the working tree.
+/// Files with the different stat are hashed to ignore touched files.
+fn is_dirty(work_tree: &Path, index_path: &Path) -> Option<bool> {
+    let index = std::fs::read(index_path).ok()?;
+    let mut reader = IndexReader {
+        data: &index,
+        position: 0,
+    };
+
+    if reader.bytes(4)? != b"DIRC" {
+        return None;
+    }
+    let version = reader.u32()?;
+    if !(2..=4).contains(&version) {
+        return None;
+    }
+    let count = reader.u32()?;
+
+    let mut path = Vec::<u8>::new();
+    for _ in 0..count {
+        let entry_start = reader.position;
+        reader.bytes(8)?; // ctime
+        let mtime = reader.u32()?;
+        reader.bytes(4 + 4 + 4)?; // mtime nanoseconds, dev, ino
+        let mode = reader.u32()?;
+        reader.bytes(4 + 4)?; // uid, gid
+        let size = reader.u32()?;
+        let hash = reader.bytes(20)?;
+        let flags = reader.u16()?;
+        let extended_flags = if version >= 3 && flags & FLAG_EXTENDED != 0 {
+            reader.u16()?
+        } else {
+            0
+        };
+
+        if version == 4 {
+            let remove = reader.varint()?;
+            path.truncate(path.len().checked_sub(remove)?);
+            path.extend_from_slice(reader.until_nul()?);
+        } else {
+            path.clear();
+            path.extend_from_slice(reader.until_nul()?);
+            // entries are padded with 1-8 nul bytes to multiple of 8 bytes
+            let length = reader.position - entry_start;
+            reader.bytes((8 - length % 8) % 8)?;
+        }
+
+        if flags & FLAG_STAGE_MASK != 0 {
+            return Some(true); // unmerged
+        }
+        if extended_flags & EXTENDED_FLAG_SKIP_WORKTREE != 0 {
+            continue;
+        }
+        if mode & MODE_TYPE_MASK == MODE_GITLINK {
+            continue; // submodules
+        }
+
+        let file_path = work_tree.join(String::from_utf8_lossy(&path).as_ref());
+        let Ok(metadata) = std::fs::symlink_metadata(&file_path) else {
+            return Some(true); // deleted
+        };
+        let file_mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|x| x.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|x| x.as_secs() as u32);
+        if metadata.len() as u32 == size && file_mtime == Some(mtime) {
+            continue;
+        }
+        if mode & MODE_TYPE_MASK == MODE_SYMLINK {
+            return Some(true);
+        }
+
+        let Ok(content) = std::fs::read(&file_path) else {
+            return Some(true);
+        };
+        if blob_hash(&content) != hash {
+            // the file may be checked out with CRLF
+            let converted = crlf_to_lf(&content);
+            if converted.len() == content.len() || blob_hash(&converted) != hash {
+                return Some(true);
+            }
+        }
+    }
+
+    Some(false)
+}
+
+fn blob_hash(content: &[u8]) -> [u8; 20] {
+    let mut hasher = Sha1::new();
+    hasher.update(format!("blob {}\0", content.len()).as_bytes());
+    hasher.update(content);
+    hasher.finalize().into()
+}
+
+fn crlf_to_lf(content: &[u8]) -> Vec<u8> {
+    let mut converted = Vec::with_capacity(content.len());
+    for (index, &byte) in content.iter().enumerate() {
+        if byte == b'\r' && content.get(index + 1) == Some(&b'\n') {
+            continue;
+        }
+        converted.push(byte);
+    }
+    converted
+}
+
+struct IndexReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> IndexReader<'a> {
+    fn bytes(&mut self, length: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.position..self.position + length)?;
+        self.position += length;
+        Some(bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_be_bytes(self.bytes(2)?.try_into().ok()?))
+    }
+
+    fn until_nul(&mut self) -> Option<&'a [u8]> {
+        let rest = self.data.get(self.position..)?;
+        let length = rest.iter().position(|&x| x == 0)?;
+        self.position += length + 1;
+        Some(&rest[..length])
+    }
+
+    /// The offset encoding of git
+    fn varint(&mut self) -> Option<usize> {
+        let mut byte = self.bytes(1)?[0];
+        let mut value = (byte & 0x7f) as usize;
+        while byte & 0x80 != 0 {
+            byte = self.bytes(1)?[0];
+            // checked_shl does not detect the lost bits
+            value = value.checked_add(1)?.checked_mul(0x80)? | (byte & 0x7f) as usize;
+        }
+        Some(value)
+    }
+}
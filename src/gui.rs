@@ -2,8 +2,9 @@
 
 use console_log_saver::*;
 use libui::controls::{
-    Button, Checkbox, Combobox, Group, Label, ProgressBar, ProgressBarValue, SelectionMode, Table,
-    TableDataSource, TableModel, TableParameters, TableValue, TableValueType, VerticalBox,
+    Button, Checkbox, Combobox, Group, Label, PasswordEntry, ProgressBar, ProgressBarValue,
+    SelectionMode, Table, TableDataSource, TableModel, TableParameters, TableValue, TableValueType,
+    TextEntry, VerticalBox,
 };
 use libui::prelude::*;
 use std::any::Any;
@@ -81,6 +82,10 @@ fn main() {
                     return;
                 };
                 let mut layout = layout.borrow_mut();
+                // ask the passphrase before choosing the file not to discard the choice
+                let Some(passphrase) = get_passphrase(&mut layout) else {
+                    return;
+                };
                 //let Some(path) = win.save_file() else {
                 let Some(path) = rfd::FileDialog::new()
                     .set_file_name("log.txt")
@@ -92,12 +97,7 @@ fn main() {
                 let Some(&selecting) = layout.table.selection().get(0) else {
                     return;
                 };
-                let Some(pid) = data
-                    .borrow()
-                    .unity_process
-                    .get(selecting as usize)
-                    .map(|x| x.pid())
-                else {
+                let Some(target) = data.borrow().capture_target(selecting as usize) else {
                     return;
                 };
 
@@ -108,8 +108,9 @@ fn main() {
                 thread::spawn({
                     move || {
                         let unwind = catch_unwind(|| {
-                            let result = run_console_log_saver(pid, &config);
-                            std::fs::write(path, result.unwrap())
+                            let result = target.capture(&config);
+                            let result = encrypt_if_needed(result.unwrap(), &passphrase);
+                            std::fs::write(path, result)
                         });
 
                         queue.queue_main(|layout| {
@@ -156,22 +157,24 @@ fn main() {
                 let Some(&selecting) = layout.table.selection().get(0) else {
                     return;
                 };
-                let Some(pid) = data
-                    .borrow()
-                    .unity_process
-                    .get(selecting as usize)
-                    .map(|x| x.pid())
-                else {
+                let Some(target) = data.borrow().capture_target(selecting as usize) else {
                     return;
                 };
 
                 let config = create_config(&layout);
+                let Some(passphrase) = get_passphrase(&mut layout) else {
+                    return;
+                };
 
                 layout.start_fetch();
                 thread::spawn({
                     let queue = libui::EventQueueWithData::new(&ui, layout_weak.clone());
                     move || {
-                        let unwind = catch_unwind(|| run_console_log_saver(pid, &config));
+                        let unwind = catch_unwind(|| {
+                            target
+                                .capture(&config)
+                                .map(|x| encrypt_if_needed(x, &passphrase))
+                        });
 
                         queue.queue_main(|layout| {
                             let Some(layout) = layout.upgrade() else {
@@ -229,29 +232,87 @@ fn panic_to_str<'a>(panic: &'a (dyn Any + Send + 'static)) -> &'a str {
 
 struct UnityProcessList {
     unity_process: Vec<UnityProcess>,
+    // the projects in Unity Hub not opened now, shown after the processes
+    offline_projects: Vec<UnityHubProject>,
+}
+
+enum CaptureTarget {
+    Process(ProcessId),
+    Offline(std::path::PathBuf),
+}
+
+impl CaptureTarget {
+    fn capture(&self, config: &ConsoleLogSaverConfig) -> Result<String, String> {
+        match self {
+            CaptureTarget::Process(pid) => {
+                run_console_log_saver(*pid, config).map_err(|e| e.to_string())
+            }
+            CaptureTarget::Offline(project_path) => {
+                run_offline_console_log_saver(project_path, config).map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+#[derive(Eq, PartialEq)]
+enum RowKey<'a> {
+    Process(ProcessId, &'a std::path::Path),
+    Offline(&'a std::path::Path),
 }
 
 impl UnityProcessList {
     fn new() -> UnityProcessList {
         UnityProcessList {
             unity_process: Vec::new(),
+            offline_projects: Vec::new(),
         }
     }
 
+    fn capture_target(&self, row: usize) -> Option<CaptureTarget> {
+        if let Some(process) = self.unity_process.get(row) {
+            return Some(CaptureTarget::Process(process.pid()));
+        }
+        let project = self.offline_projects.get(row - self.unity_process.len())?;
+        Some(CaptureTarget::Offline(project.path().to_owned()))
+    }
+
+    fn row_keys(&self) -> Vec<RowKey<'_>> {
+        let processes = self
+            .unity_process
+            .iter()
+            .map(|x| RowKey::Process(x.pid(), x.project_path()));
+        let offline = self
+            .offline_projects
+            .iter()
+            .map(|x| RowKey::Offline(x.path()));
+        processes.chain(offline).collect()
+    }
+
     fn reload_unity(&mut self, model: &mut TableModel) {
-        let prev_data = std::mem::replace(&mut self.unity_process, find_unity_processes());
-        self.unity_process = find_unity_processes();
+        let unity_process = find_unity_processes();
+        let offline_projects = unity_hub_projects()
+            .into_iter()
+            .filter(|project| {
+                let path = project.path().to_string_lossy();
+                !unity_process.iter().any(|x| x.is_for_project(&path))
+            })
+            .collect();
+        let prev_data = UnityProcessList {
+            unity_process: std::mem::replace(&mut self.unity_process, unity_process),
+            offline_projects: std::mem::replace(&mut self.offline_projects, offline_projects),
+        };
 
-        let mut prev_pid_iter = prev_data.iter();
-        let mut new_iter = self.unity_process.iter();
+        let prev_keys = prev_data.row_keys();
+        let new_keys = self.row_keys();
+        let mut prev_pid_iter = prev_keys.iter();
+        let mut new_iter = new_keys.iter();
 
         let mut index = 0;
         loop {
             match (prev_pid_iter.next(), new_iter.next()) {
-                (Some(prev), Some(new)) if prev.pid() == new.pid() => {
-                    if prev.project_path() != new.project_path() {
-                        model.notify_row_changed(index)
-                    }
+                (Some(prev), Some(new)) if prev == new => {
+                    // running time and names may be changed
+                    model.notify_row_changed(index);
                     index += 1;
                 }
 
@@ -281,30 +342,57 @@ impl UnityProcessList {
 
 impl TableDataSource for UnityProcessList {
     fn num_columns(&mut self) -> i32 {
-        2
+        4
     }
 
     fn num_rows(&mut self) -> i32 {
-        self.unity_process.len().try_into().unwrap_or(i32::MAX)
+        (self.unity_process.len() + self.offline_projects.len())
+            .try_into()
+            .unwrap_or(i32::MAX)
     }
 
     fn column_type(&mut self, column: i32) -> TableValueType {
         match column {
             0 => TableValueType::String,
             1 => TableValueType::String,
+            2 => TableValueType::String,
+            3 => TableValueType::String,
             _ => unreachable!(),
         }
     }
 
     fn cell(&mut self, column: i32, row: i32) -> TableValue {
-        let row = &self.unity_process[row as usize];
+        let Some(row) = self.unity_process.get(row as usize) else {
+            let project = &self.offline_projects[row as usize - self.unity_process.len()];
+            return match column {
+                0 => TableValue::String("-".to_owned()),
+                1 => TableValue::String(format!(
+                    "{} ({})",
+                    project.title().unwrap_or_default(),
+                    project.path().to_string_lossy()
+                )),
+                2 => TableValue::String(project.editor_version().unwrap_or_default().to_owned()),
+                3 => TableValue::String("-".to_owned()),
+                _ => unreachable!(),
+            };
+        };
         match column {
             0 => TableValue::String(row.pid().to_string()),
             1 => TableValue::String(format!(
-                "{} ({})",
-                row.project_path().file_name().unwrap().to_string_lossy(),
+                "{}{} ({})",
+                row.display_name().unwrap_or_default(),
+                if row.is_batch_mode() {
+                    " [batchmode]"
+                } else {
+                    ""
+                },
                 row.project_path().to_string_lossy()
             )),
+            2 => TableValue::String(row.editor_version().unwrap_or_default().to_owned()),
+            3 => {
+                let elapsed = row.start_time().elapsed().unwrap_or_default().as_secs();
+                TableValue::String(format!("{}:{:02}", elapsed / 3600, elapsed / 60 % 60))
+            }
             _ => unreachable!(),
         }
     }
@@ -323,6 +411,28 @@ fn create_config(layout: &UILayout) -> ConsoleLogSaverConfig {
     config
 }
 
+/// Returns `Some(None)` if encryption is disabled and `None` if passphrase is empty.
+fn get_passphrase(layout: &mut UILayout) -> Option<Option<String>> {
+    if !layout.encrypt_output.checked() {
+        return Some(None);
+    }
+    let passphrase = layout.passphrase.value();
+    if passphrase.is_empty() {
+        let msg = layout.messages.passphrase_is_empty;
+        layout.start_fetch();
+        layout.finish_fetch(msg);
+        return None;
+    }
+    Some(Some(passphrase))
+}
+
+fn encrypt_if_needed(log: String, passphrase: &Option<String>) -> String {
+    match passphrase {
+        Some(passphrase) => encrypt_cls_file(&log, passphrase),
+        None => log,
+    }
+}
+
 enum VersionInfo {
     Fetching,
     Latest,
@@ -342,6 +452,8 @@ struct UILayout {
     hide_user_name: Checkbox,
     hide_user_home_path: Checkbox,
     hide_aws_upload_signature: Checkbox,
+    encrypt_output: Checkbox,
+    passphrase: PasswordEntry,
     save_to_file: Button,
     copy_to_clipboard: Button,
     vbox: VerticalBox,
@@ -366,7 +478,9 @@ impl UILayout {
             let mut table = Table::new(parameters);
             table.append_text_column(m.pid, 0, Table::COLUMN_READONLY);
             table.append_text_column(m.project_name_project_path, 1, Table::COLUMN_READONLY);
-            table.set_column_width(1, 1000);
+            table.append_text_column(m.unity_version, 2, Table::COLUMN_READONLY);
+            table.append_text_column(m.running_time, 3, Table::COLUMN_READONLY);
+            table.set_column_width(1, 800);
             table.set_selection_mode(SelectionMode::ZeroOrOne);
             vbox.append(table.clone(), LayoutStrategy::Stretchy);
 
@@ -399,6 +513,13 @@ impl UILayout {
             security_settings_box
                 .append(hide_aws_upload_signature.clone(), LayoutStrategy::Compact);
 
+            let encrypt_output = Checkbox::new("");
+            security_settings_box.append(encrypt_output.clone(), LayoutStrategy::Compact);
+
+            let mut passphrase = PasswordEntry::new();
+            passphrase.disable();
+            security_settings_box.append(passphrase.clone(), LayoutStrategy::Compact);
+
             security_settings_group.set_child(security_settings_box);
             vbox.append(security_settings_group.clone(), LayoutStrategy::Compact);
 
@@ -428,6 +549,8 @@ impl UILayout {
                 hide_user_name,
                 hide_user_home_path,
                 hide_aws_upload_signature,
+                encrypt_output,
+                passphrase,
                 save_to_file,
                 copy_to_clipboard,
                 vbox,
@@ -475,6 +598,20 @@ impl UILayout {
                 }
             });
 
+            layout.encrypt_output.on_toggled(ui, {
+                let weak = Rc::downgrade(&result);
+                move |checked| {
+                    if let Some(layout) = weak.upgrade() {
+                        let mut layout = layout.borrow_mut();
+                        if checked {
+                            layout.passphrase.enable();
+                        } else {
+                            layout.passphrase.disable();
+                        }
+                    }
+                }
+            });
+
             layout.save_to_file.disable();
             layout.copy_to_clipboard.disable();
 
@@ -543,6 +680,7 @@ impl UILayout {
         self.hide_user_home_path.set_text(m.hide_user_home_path);
         self.hide_aws_upload_signature
             .set_text(m.hide_aws_upload_signature);
+        self.encrypt_output.set_text(m.encrypt_output);
         self.save_to_file.set_text(m.save_to_file);
         self.copy_to_clipboard.set_text(m.copy_to_clipboard);
     }
@@ -606,6 +744,8 @@ struct Messages {
     locale_name: &'static str,
     pid: &'static str,
     project_name_project_path: &'static str,
+    unity_version: &'static str,
+    running_time: &'static str,
     refresh_unity_list: &'static str,
     version_checking_for_updates: &'static str,
     version_it_is_latest: &'static str,
@@ -618,6 +758,8 @@ struct Messages {
     hide_user_name: &'static str,
     hide_user_home_path: &'static str,
     hide_aws_upload_signature: &'static str,
+    encrypt_output: &'static str,
+    passphrase_is_empty: &'static str,
     save_to_file: &'static str,
     text_files_star_txt: &'static str,
     copy_to_clipboard: &'static str,
@@ -633,6 +775,8 @@ impl Messages {
                 locale_name: "English",
                 pid: "PID",
                 project_name_project_path: "Project Name (Project Path)",
+                unity_version: "Unity Version",
+                running_time: "Running Time",
                 refresh_unity_list: "Refresh Unity List",
                 version_checking_for_updates: "Version {0}. Checking for updates...",
                 version_it_is_latest: "Version {0}. It's Latest.",
@@ -646,6 +790,8 @@ impl Messages {
                 hide_user_name: "Hide User Name",
                 hide_user_home_path: "Hide User Home Path",
                 hide_aws_upload_signature: "Hide AWS Upload Signature",
+                encrypt_output: "Encrypt with Passphrase",
+                passphrase_is_empty: "Please enter the passphrase to encrypt",
                 save_to_file: "Save to File",
                 text_files_star_txt: "Text Files (*.txt)",
                 copy_to_clipboard: "Copy to Clipboard",
@@ -662,6 +808,8 @@ impl Messages {
                 locale_name: "日本語",
                 pid: "PID",
                 project_name_project_path: "Project名 (Projectの場所)",
+                unity_version: "Unityのバージョン",
+                running_time: "起動してからの時間",
                 refresh_unity_list: "Unityの一覧を更新する",
                 security_settings: "Security Settings",
                 unity_version_required: "Unityのバージョン (Required)",
@@ -669,6 +817,8 @@ impl Messages {
                 hide_user_name: "ユーザ名を隠す",
                 hide_user_home_path: "ユーザホームのパスを隠す",
                 hide_aws_upload_signature: "AWS Upload Signatureを隠す",
+                encrypt_output: "パスフレーズで暗号化する",
+                passphrase_is_empty: "暗号化のためのパスフレーズを入力してください",
                 save_to_file: "ファイルに保存",
                 text_files_star_txt: "テキストファイル (*.txt)",
                 copy_to_clipboard: "コピーする",
@@ -1,11 +1,43 @@
+mod assembly_graph;
+mod assembly_metadata;
+mod bundle;
+mod capture_cursor;
 mod check_for_update;
 mod cls_file;
+mod compiler_message;
+mod editor_instance;
+mod editor_log;
+mod encryption;
+mod git_state;
+mod log_filter;
+mod player_settings;
+mod plugin_inventory;
+mod process_kind;
 mod process_remote;
+mod project_files;
+mod unity_hub;
+mod watch;
 
-use crate::cls_file::{ClsFileBuilder, ClsHeadingBuilder};
+pub use crate::bundle::{
+    AttachmentRedaction, BundleAttachment, BundleError, ClsBundle, ClsBundleBuilder,
+};
+pub use crate::capture_cursor::{CaptureCursor, InvalidCaptureCursor};
+use crate::cls_file::{single_line, ClsBodyBuilder, ClsFileBuilder, ClsHeadingBuilder};
+pub use crate::cls_file::{ClsFile, ClsParseError, ClsSection};
+use crate::compiler_message::CompilerMessage;
+pub use crate::encryption::{
+    decrypt_cls_file, encrypt_cls_file, is_encrypted_cls_file, DecryptError,
+};
+pub use crate::log_filter::LogSeverity;
+use crate::log_filter::{filter_entries, LogEntry};
+pub use crate::process_kind::UnityProcessKind;
 pub use crate::process_remote::ProcessId;
 use crate::process_remote::ProcessRemoteError::NonUtf8LogContents;
 use crate::process_remote::{base_err, ProcessRemoteError};
+pub use crate::unity_hub::{
+    unity_hub_editors, unity_hub_projects, UnityHubEditor, UnityHubProject,
+};
+pub use crate::watch::{watch_console_log_saver, WatchCapture};
 use byteorder::{NativeEndian, ReadBytesExt};
 pub use check_for_update::check_for_update;
 use regex::Regex;
@@ -17,10 +49,23 @@ use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, UpdateKind};
 
 pub static CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+static VENDOR: &str = concat!(
+    "ConsoleLogSaver/",
+    env!("CARGO_PKG_VERSION"),
+    " (CLS-LLDB-RS)"
+);
+
 #[derive(Debug)]
 pub struct UnityProcess {
     pid: ProcessId,
+    kind: UnityProcessKind,
     project_path: std::path::PathBuf,
+    editor_version: Option<String>,
+    exe_path: std::path::PathBuf,
+    start_time: u64,
+    display_name: Option<String>,
+    batch_mode: bool,
+    editor_log_path: Option<std::path::PathBuf>,
 }
 
 impl UnityProcess {
@@ -28,19 +73,103 @@ impl UnityProcess {
         self.pid
     }
 
+    pub fn kind(&self) -> UnityProcessKind {
+        self.kind
+    }
+
     pub fn project_path(&self) -> &std::path::Path {
         &self.project_path
     }
+
+    /// The version of the editor, from the path of the editor or `ProjectVersion.txt` of the project.
+    pub fn editor_version(&self) -> Option<&str> {
+        self.editor_version.as_deref()
+    }
+
+    /// The path of the editor executable.
+    pub fn exe_path(&self) -> &std::path::Path {
+        &self.exe_path
+    }
+
+    /// The time the process was started.
+    pub fn start_time(&self) -> std::time::SystemTime {
+        std::time::UNIX_EPOCH + std::time::Duration::from_secs(self.start_time)
+    }
+
+    /// The product name in the project settings, or the name of the project folder.
+    pub fn display_name(&self) -> Option<&str> {
+        self.display_name.as_deref().or(self.project_name())
+    }
+
+    /// Returns true if the editor is running with `-batchmode`.
+    pub fn is_batch_mode(&self) -> bool {
+        self.batch_mode
+    }
+
+    /// Returns true if the process is a standalone player built with Unity.
+    /// The project path of the player is the folder or the app bundle of the build.
+    pub fn is_player(&self) -> bool {
+        self.kind == UnityProcessKind::Player
+    }
+
+    /// The log file the process writes to, specified with `-logFile` or the default `Editor.log`.
+    /// None if the log is written to stdout with `-logFile -`.
+    pub fn editor_log_path(&self) -> Option<&std::path::Path> {
+        self.editor_log_path.as_deref()
+    }
+
+    /// The name of the project, which is the name of the project folder.
+    pub fn project_name(&self) -> Option<&str> {
+        self.project_path.file_name()?.to_str()
+    }
+
+    /// Returns true if the process is for the project specified with path or name.
+    ///
+    /// Paths are compared after canonicalization, and case-insensitively on Windows.
+    /// Names are compared with the project folder name case-insensitively.
+    pub fn is_for_project(&self, path_or_name: &str) -> bool {
+        let is_path = path_or_name.contains(['/', std::path::MAIN_SEPARATOR])
+            || std::path::Path::new(path_or_name).is_dir();
+        if is_path {
+            fn normalize(path: &std::path::Path) -> String {
+                let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+                let path = path.to_string_lossy();
+                let path = path.trim_end_matches(['/', std::path::MAIN_SEPARATOR]);
+                if cfg!(windows) {
+                    path.to_lowercase()
+                } else {
+                    path.to_owned()
+                }
+            }
+            normalize(&self.project_path) == normalize(path_or_name.as_ref())
+        } else {
+            self.project_name()
+                .map(|x| x.eq_ignore_ascii_case(path_or_name))
+                .unwrap_or(false)
+        }
+    }
 }
 
 pub fn find_unity_processes() -> Vec<UnityProcess> {
-    #[cfg(target_os = "macos")]
-    let exe_name: &std::path::Path = "Contents/MacOS/Unity".as_ref();
-    #[cfg(target_os = "windows")]
-    let exe_name: &std::path::Path = "Unity.exe".as_ref();
-    #[cfg(target_os = "linux")]
-    let exe_name: &std::path::Path = "Unity".as_ref();
+    find_unity_processes_in(&[])
+}
+
+/// Finds unity editors like [`find_unity_processes`].
+///
+/// The editors without `-projectPath` in the command line are found with `Library/EditorInstance.json`
+/// in the search roots, the direct children of the search roots, and the projects listed in Unity Hub.
+pub fn find_unity_processes_in(search_roots: &[std::path::PathBuf]) -> Vec<UnityProcess> {
+    find_unity_processes_of_kinds(search_roots, &[UnityProcessKind::Editor])
+}
 
+/// Finds unity processes of the kinds.
+///
+/// The helper processes without project path in the command line
+/// are reported with the project of the parent editor, or excluded if the parent is not an editor.
+pub fn find_unity_processes_of_kinds(
+    search_roots: &[std::path::PathBuf],
+    kinds: &[UnityProcessKind],
+) -> Vec<UnityProcess> {
     let mut sysinfo = sysinfo::System::new();
 
     sysinfo.refresh_processes_specifics(
@@ -52,31 +181,92 @@ pub fn find_unity_processes() -> Vec<UnityProcess> {
     );
     sysinfo.refresh_processes(ProcessesToUpdate::All, true);
 
-    let mut unity_processes = Vec::new();
+    let is_editor = |pid: sysinfo::Pid| {
+        sysinfo
+            .process(pid)
+            .and_then(|x| x.exe())
+            .map(process_kind::is_editor_exe)
+            .unwrap_or(false)
+    };
+
+    let mut candidates = Vec::new();
     for (pid, proc) in sysinfo.processes() {
         if proc.thread_kind().is_some() {
             // we don't want to include threads
             continue;
         }
         let Some(exe) = proc.exe() else { continue };
-        if !exe.ends_with(exe_name) {
+        let parent_is_editor = proc.parent().map(is_editor).unwrap_or(false);
+        let Some(kind) = UnityProcessKind::classify(exe, proc.cmd(), parent_is_editor) else {
             continue;
-        }
+        };
+        candidates.push((*pid, proc, exe, kind));
+    }
+
+    // read lazily since this is not needed in most cases
+    let mut editor_instances = None;
+
+    let mut project_paths = std::collections::HashMap::new();
+    let mut editor_versions = std::collections::HashMap::new();
+    for &(pid, proc, _, kind) in &candidates {
         let cmd = proc.cmd();
-        if cmd.iter().any(|x| x == "-srvPort") {
-            continue; // it looks asset importer worker
+        let project_path = cmd
+            .iter()
+            .position(|x| x.eq_ignore_ascii_case("-projectPath"))
+            .and_then(|index| cmd.get(index + 1));
+        if let Some(project_path) = project_path {
+            project_paths.insert(pid, std::path::PathBuf::from(project_path));
+        } else if kind == UnityProcessKind::Player {
+            // players have no project, so the folder of the build is used instead
+            if let Some(build_path) = player_build_path(proc.exe()) {
+                project_paths.insert(pid, build_path);
+            }
+        } else if kind == UnityProcessKind::Editor {
+            // opened by double-clicking a scene, some Hub versions, or cmdline is unreadable
+            let instances = editor_instances
+                .get_or_insert_with(|| editor_instance::find_editor_instances(search_roots));
+            let pid_key = pid.as_u32() as ProcessId;
+            if let Some((project_path, instance)) = instances.get(&pid_key) {
+                project_paths.insert(pid, project_path.clone());
+                if let Some(version) = &instance.version {
+                    editor_versions.insert(pid, version.clone());
+                }
+            }
         }
-        let Some(index) = cmd.iter().position(|x| x == "-projectPath") else {
+    }
+
+    let mut unity_processes = Vec::new();
+    for (pid, proc, exe, kind) in candidates {
+        if !kinds.contains(&kind) {
             continue;
-        };
-        let Some(project_path) = cmd.get(index + 1) else {
+        }
+        let project_path = project_paths
+            .get(&pid)
+            .or_else(|| project_paths.get(&proc.parent()?));
+        let Some(project_path) = project_path else {
             continue;
         };
-        let project_path = std::path::Path::new(project_path);
+        let project_path = project_path.as_path();
 
         unity_processes.push(UnityProcess {
             pid: pid.as_u32() as ProcessId,
+            kind,
             project_path: project_path.to_owned(),
+            editor_version: editor_version_from_exe_path(exe)
+                .or_else(|| editor_versions.get(&pid).cloned())
+                .or_else(|| read_project_version(project_path)),
+            exe_path: exe.to_owned(),
+            start_time: proc.start_time(),
+            display_name: if kind == UnityProcessKind::Player {
+                process_kind::player_data_dir(exe).and_then(|x| read_player_product_name(&x))
+            } else {
+                read_product_name(project_path)
+            },
+            batch_mode: proc
+                .cmd()
+                .iter()
+                .any(|x| x.eq_ignore_ascii_case("-batchmode")),
+            editor_log_path: editor_log_path_from_cmd(proc.cmd()),
         })
     }
 
@@ -85,6 +275,147 @@ pub fn find_unity_processes() -> Vec<UnityProcess> {
     unity_processes
 }
 
+/// The folder the player is in, or the app bundle on macOS.
+fn player_build_path(exe: Option<&std::path::Path>) -> Option<std::path::PathBuf> {
+    let data = process_kind::player_data_dir(exe?)?;
+    let levels = if cfg!(target_os = "macos") { 3 } else { 1 };
+    data.ancestors().nth(levels).map(ToOwned::to_owned)
+}
+
+/// Reads the product name in `app.info` of the player, which has the company name and the product name.
+fn read_player_product_name(data_dir: &std::path::Path) -> Option<String> {
+    let content = std::fs::read_to_string(data_dir.join("app.info")).ok()?;
+    content
+        .lines()
+        .nth(1)
+        .filter(|x| !x.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+/// Finds the log file specified with `-logFile`, or the default `Editor.log`.
+fn editor_log_path_from_cmd(cmd: &[std::ffi::OsString]) -> Option<std::path::PathBuf> {
+    let Some(index) = cmd.iter().position(|x| x.eq_ignore_ascii_case("-logFile")) else {
+        return editor_log::editor_log_paths().into_iter().next();
+    };
+    // `-logFile -` or `-logFile` without path writes to stdout
+    let path = cmd.get(index + 1)?;
+    if path.to_string_lossy().starts_with('-') {
+        return None;
+    }
+    Some(std::path::PathBuf::from(path))
+}
+
+/// Finds the version from the path like `Hub/Editor/2022.3.22f1/Editor/Unity.exe`
+fn editor_version_from_exe_path(exe: &std::path::Path) -> Option<String> {
+    static REGEX: std::sync::LazyLock<Regex> =
+        std::sync::LazyLock::new(|| Regex::new(r"^\d+\.\d+\.\d+[abcfpx]\d+$").unwrap());
+    exe.components()
+        .rev()
+        .filter_map(|x| x.as_os_str().to_str())
+        .find(|x| REGEX.is_match(x))
+        .map(ToOwned::to_owned)
+}
+
+/// Reads `m_EditorVersion` of `ProjectSettings/ProjectVersion.txt`
+fn read_project_version(project_path: &std::path::Path) -> Option<String> {
+    let path = project_path.join("ProjectSettings/ProjectVersion.txt");
+    let content = std::fs::read_to_string(path).ok()?;
+    find_yaml_value(&content, "m_EditorVersion").map(ToOwned::to_owned)
+}
+
+/// Reads `productName` of `ProjectSettings/ProjectSettings.asset`
+fn read_product_name(project_path: &std::path::Path) -> Option<String> {
+    let path = project_path.join("ProjectSettings/ProjectSettings.asset");
+    let content = std::fs::read_to_string(path).ok()?;
+    find_yaml_value(&content, "productName")
+        .map(unquote_yaml_value)
+        .filter(|x| !x.is_empty())
+        .map(Cow::into_owned)
+}
+
+/// Finds the value of simple `key: value` line in the unity yaml.
+/// This does not support multi-line values.
+fn find_yaml_value<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    content.lines().find_map(|line| {
+        let (name, value) = line.trim_start().split_once(':')?;
+        (name == key).then(|| value.trim())
+    })
+}
+
+/// Unquotes the scalar value found with [`find_yaml_value`].
+///
+/// Unity writes the strings with non-ASCII characters as double-quoted scalar
+/// like `"\u30C6\u30B9\u30C8"`, and the strings with some symbols as single-quoted scalar.
+fn unquote_yaml_value(value: &str) -> Cow<'_, str> {
+    if let Some(value) = (value.strip_prefix('\''))
+        .and_then(|x| x.strip_suffix('\''))
+        .filter(|_| value.len() >= 2)
+    {
+        return Cow::Owned(value.replace("''", "'"));
+    }
+    let Some(value) = (value.strip_prefix('"'))
+        .and_then(|x| x.strip_suffix('"'))
+        .filter(|_| value.len() >= 2)
+    else {
+        return Cow::Borrowed(value);
+    };
+
+    fn hex(chars: &mut std::str::Chars, digits: usize) -> Option<u32> {
+        let hex = chars.by_ref().take(digits).collect::<String>();
+        (hex.len() == digits).then_some(())?;
+        u32::from_str_radix(&hex, 16).ok()
+    }
+
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        let code = match chars.next() {
+            Some('0') => Some(0),
+            Some('a') => Some(0x07),
+            Some('b') => Some(0x08),
+            Some('t') => Some(0x09),
+            Some('n') => Some(0x0A),
+            Some('v') => Some(0x0B),
+            Some('f') => Some(0x0C),
+            Some('r') => Some(0x0D),
+            Some('e') => Some(0x1B),
+            Some(' ') => Some(0x20),
+            Some('_') => Some(0xA0),
+            Some('N') => Some(0x85),
+            Some('L') => Some(0x2028),
+            Some('P') => Some(0x2029),
+            Some('x') => hex(&mut chars, 2),
+            Some('u') => match hex(&mut chars, 4) {
+                // surrogate pairs are written as two escapes
+                Some(high @ 0xD800..=0xDBFF) => {
+                    let mut rest = chars.clone();
+                    let low = (rest.next() == Some('\\') && rest.next() == Some('u'))
+                        .then(|| hex(&mut rest, 4))
+                        .flatten()
+                        .filter(|x| (0xDC00..=0xDFFF).contains(x));
+                    low.map(|low| {
+                        chars = rest;
+                        0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                    })
+                }
+                code => code,
+            },
+            Some('U') => hex(&mut chars, 8),
+            Some(c) => Some(c as u32),
+            None => None,
+        };
+        result.push(
+            code.and_then(char::from_u32)
+                .unwrap_or(char::REPLACEMENT_CHARACTER),
+        );
+    }
+    Cow::Owned(result)
+}
+
 #[non_exhaustive]
 #[derive(Debug, Clone)]
 pub struct ConsoleLogSaverConfig {
@@ -92,6 +423,35 @@ pub struct ConsoleLogSaverConfig {
     pub hide_user_home: bool,
     pub hide_os_info: bool,
     pub hide_aws_upload_signature: bool,
+    pub custom_rules: Vec<RedactionRule>,
+    /// The words never hidden even if some rule matches to a part of them. Case is ignored.
+    pub allowlist: Vec<String>,
+    pub include_log: bool,
+    pub include_warning: bool,
+    pub include_error: bool,
+    /// If specified, only the newest entries up to this count are saved.
+    pub max_entries: Option<usize>,
+    /// If specified, only the entries the message matches are saved.
+    pub include_regex: Option<Regex>,
+    /// If specified, the entries the message matches are not saved.
+    pub exclude_regex: Option<Regex>,
+    /// If true, identical messages are saved once with `Repeat-Count` header.
+    pub deduplicate: bool,
+    /// If specified, only the entries logged after the capture with this cursor are saved.
+    pub since: Option<CaptureCursor>,
+    /// If true, the native plugins, managed DLLs and assembly definitions in `Assets` and packages are listed.
+    /// This reads all DLLs in the project so it's disabled by default.
+    pub include_plugin_inventory: bool,
+    /// If true, the asmdef and asmref files in `Assets` and packages are saved as sections
+    /// with the references, missing references and reference cycles.
+    pub include_assembly_graph: bool,
+    /// If specified, the last kilobytes of the editor log are saved as `editor-log` section.
+    pub editor_log_tail_kb: Option<u64>,
+    /// If true, the last kilobytes of `Editor-prev.log` are also saved with `editor_log_tail_kb`.
+    pub include_previous_editor_log: bool,
+    /// If true, `Player.log` of the player built from the project is saved as `player-log` section.
+    /// The end of the log is saved with the size of `editor_log_tail_kb` if specified.
+    pub include_player_log: bool,
 }
 
 impl Default for ConsoleLogSaverConfig {
@@ -101,22 +461,136 @@ impl Default for ConsoleLogSaverConfig {
             hide_user_home: true,
             hide_os_info: false,
             hide_aws_upload_signature: true,
+            custom_rules: vec![],
+            allowlist: vec![],
+            include_log: true,
+            include_warning: true,
+            include_error: true,
+            max_entries: None,
+            include_regex: None,
+            exclude_regex: None,
+            deduplicate: false,
+            since: None,
+            include_plugin_inventory: false,
+            include_assembly_graph: false,
+            editor_log_tail_kb: None,
+            include_previous_editor_log: false,
+            include_player_log: false,
         }
     }
 }
 
-struct ReplaceSet {
-    pairs: Vec<(&'static Regex, &'static str)>,
+impl ConsoleLogSaverConfig {
+    /// The size of the log files to save, with `editor_log_tail_kb` or the default size.
+    fn editor_log_max_bytes(&self) -> u64 {
+        self.editor_log_tail_kb
+            .map_or(editor_log::DEFAULT_MAX_LOG_BYTES, |kb| {
+                kb.saturating_mul(1024)
+            })
+    }
+
+    /// Returns the names of rules that only hide whole words
+    /// because the text to hide is too short.
+    pub fn weakened_rules(&self) -> Vec<&str> {
+        let mut names = ReplaceSet::new(self)
+            .rules
+            .iter()
+            .filter(|x| x.whole_word)
+            .map(|x| x.name)
+            .collect::<Vec<_>>();
+        names.dedup();
+        names
+    }
+}
+
+/// Texts shorter than this are hidden only as a whole word
+/// since short names like `ai` or `test` are often a part of other words.
+const MIN_SUBSTRING_MATCH_LENGTH: usize = 6;
+
+/// Returns true if the text should be hidden only as a whole word.
+/// Non-ASCII names like CJK names are short but not separated from other words with spaces.
+fn is_short_text(text: &str) -> bool {
+    text.is_ascii() && text.len() < MIN_SUBSTRING_MATCH_LENGTH
 }
 
-impl ReplaceSet {
-    fn new(config: &ConsoleLogSaverConfig) -> Self {
-        let mut regex_pairs = vec![];
+/// The user-defined rule to hide data in the log.
+#[derive(Debug, Clone)]
+pub struct RedactionRule {
+    name: String,
+    pattern: Regex,
+    whole_word: bool,
+}
+
+impl RedactionRule {
+    /// Creates the rule. The name will be shown in `Hidden-Data` header and as `${name}` in the log.
+    pub fn new(name: impl Into<String>, pattern: Regex) -> Self {
+        let name = name.into();
+        if name.is_empty() || name.contains(['\r', '\n', '{', '}']) {
+            panic!("invalid rule name: {name:?}")
+        }
+        Self {
+            name,
+            pattern,
+            whole_word: false,
+        }
+    }
+
+    /// Creates the rule that hides the text, ignoring case.
+    /// If the text is short, it's hidden only as a whole word.
+    pub fn text(name: impl Into<String>, text: &str) -> Self {
+        let pattern = regex::RegexBuilder::new(&regex::escape(text))
+            .case_insensitive(true)
+            .build()
+            .expect("failed to create regex");
+        let mut rule = Self::new(name, pattern);
+        rule.whole_word = is_short_text(text);
+        rule
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn pattern(&self) -> &Regex {
+        &self.pattern
+    }
+}
+
+struct ReplaceSet<'a> {
+    rules: Vec<ReplaceRule<'a>>,
+    allowlist: Option<Regex>,
+}
+
+struct ReplaceRule<'a> {
+    regex: &'a Regex,
+    name: &'a str,
+    whole_word: bool,
+}
+
+impl<'a> ReplaceRule<'a> {
+    fn new(regex: &'a Regex, name: &'a str) -> Self {
+        Self {
+            regex,
+            name,
+            whole_word: false,
+        }
+    }
+}
+
+impl<'a> ReplaceSet<'a> {
+    fn new(config: &'a ConsoleLogSaverConfig) -> Self {
+        let mut rules = vec![];
 
         if config.hide_user_home {
-            static REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+            // (the regex, and whether the home is hidden only as a whole word)
+            static REGEX: std::sync::LazyLock<(Regex, bool)> = std::sync::LazyLock::new(|| {
                 let home = home::home_dir().expect("failed to get home directory");
                 let mut regex = String::new();
+                // `/home/ai` should not match `/home/aiden`
+                let whole_word = match home.components().next_back() {
+                    Some(Component::Normal(name)) => is_short_text(&name.to_string_lossy()),
+                    _ => false,
+                };
 
                 let mut last_separator = true;
                 for x in home.components() {
@@ -125,14 +599,14 @@ impl ReplaceSet {
                             regex.push_str(&regex::escape(&prefix.as_os_str().to_string_lossy()));
                         }
                         Component::RootDir => {
-                            regex.push_str(&r#"[/\\]"#);
+                            regex.push_str(PATH_SEPARATOR_PATTERN);
                             last_separator = true;
                         }
                         Component::Normal(normal) => {
                             if !last_separator {
-                                regex.push_str(&r#"[/\\]"#);
+                                regex.push_str(PATH_SEPARATOR_PATTERN);
                             }
-                            regex.push_str(&regex::escape(&normal.to_string_lossy()));
+                            regex.push_str(&path_component_pattern(&normal.to_string_lossy()));
                             last_separator = false;
                         }
                         Component::CurDir => panic!("should not happen"),
@@ -140,29 +614,70 @@ impl ReplaceSet {
                     }
                 }
 
-                regex::RegexBuilder::new(&regex)
+                let regex = regex::RegexBuilder::new(&regex)
                     .case_insensitive(true)
                     .build()
-                    .expect("failed to create regex")
+                    .expect("failed to create regex");
+                (regex, whole_word)
+            });
+            rules.push(ReplaceRule {
+                regex: &REGEX.0,
+                name: "user-home",
+                whole_word: REGEX.1,
             });
-            regex_pairs.push((REGEX.deref(), "user-home"));
         }
 
         if config.hide_user_name {
-            static REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
-                let user_name = whoami::username();
-                regex::RegexBuilder::new(&regex::escape(&user_name))
-                    .case_insensitive(true)
-                    .build()
-                    .expect("failed to create regex")
-            });
-            regex_pairs.push((REGEX.deref(), "user-name"));
+            // (names hidden as a part of words, short names hidden only as whole words)
+            static REGEX: std::sync::LazyLock<(Option<Regex>, Option<Regex>)> =
+                std::sync::LazyLock::new(|| {
+                    let mut names = vec![whoami::username()];
+                    if let Ok(real_name) = whoami::fallible::realname() {
+                        let real_name = real_name.trim();
+                        if !real_name.is_empty() && !names.iter().any(|x| x == real_name) {
+                            names.push(real_name.to_owned());
+                        }
+                    }
+
+                    let (short, long) = names.iter().partition::<Vec<_>, _>(|x| is_short_text(x));
+
+                    let create_regex = |names: Vec<&String>| {
+                        let mut patterns = vec![];
+                        for name in names {
+                            patterns.extend(encoded_patterns(name));
+                            if let Some(short_name) = short_name_pattern(name) {
+                                patterns.push(short_name);
+                            }
+                        }
+                        if patterns.is_empty() {
+                            return None;
+                        }
+
+                        let regex = regex::RegexBuilder::new(&join_alternatives(patterns))
+                            .case_insensitive(true)
+                            .build()
+                            .expect("failed to create regex");
+                        Some(regex)
+                    };
+
+                    (create_regex(long), create_regex(short))
+                });
+            if let Some(regex) = &REGEX.0 {
+                rules.push(ReplaceRule::new(regex, "user-name"));
+            }
+            if let Some(regex) = &REGEX.1 {
+                rules.push(ReplaceRule {
+                    regex,
+                    name: "user-name",
+                    whole_word: true,
+                });
+            }
         }
 
         if config.hide_aws_upload_signature {
             static REGEX: std::sync::LazyLock<Regex> =
                 std::sync::LazyLock::new(|| Regex::new(r"(?<prefix>Signature=)[^&\s]+").unwrap());
-            regex_pairs.push((REGEX.deref(), "signature-param"));
+            rules.push(ReplaceRule::new(REGEX.deref(), "signature-param"));
         }
 
         // always hidden data
@@ -172,7 +687,7 @@ impl ReplaceSet {
             static REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
                 Regex::new(r"(?<prefix>AWSAccessKeyId=)[^&\s]+").unwrap()
             });
-            regex_pairs.push((REGEX.deref(), "aws-access-key-id-param"));
+            rules.push(ReplaceRule::new(REGEX.deref(), "aws-access-key-id-param"));
         }
 
         {
@@ -180,34 +695,46 @@ impl ReplaceSet {
             static REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
                 Regex::new(r##"(?<prefix>"assetUrl"\s*:\s*")((?:[^\u0000-\u001F"\\]|\\(?:u[a-fA-F0-9]{4}|[^"\\/bfnrt]))*)(?<suffix>")"##).unwrap()
             });
-            regex_pairs.push((REGEX.deref(), "asset-url"));
+            rules.push(ReplaceRule::new(REGEX.deref(), "asset-url"));
         }
 
-        Self { pairs: regex_pairs }
+        for rule in &config.custom_rules {
+            rules.push(ReplaceRule {
+                regex: &rule.pattern,
+                name: rule.name.as_str(),
+                whole_word: rule.whole_word,
+            });
+        }
+
+        let allowlist = config
+            .allowlist
+            .iter()
+            .filter(|x| !x.is_empty())
+            .map(|x| regex::escape(x))
+            .collect::<Vec<_>>();
+        let allowlist = (!allowlist.is_empty()).then(|| {
+            regex::RegexBuilder::new(&join_alternatives(allowlist))
+                .case_insensitive(true)
+                .build()
+                .expect("failed to create regex")
+        });
+
+        Self { rules, allowlist }
     }
 
-    fn replace_all<'a, 'b>(&'a self, input: Cow<'b, str>) -> Cow<'b, str> {
+    fn names(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.rules.iter().map(|x| x.name)
+    }
+
+    fn replace_all<'b>(&self, input: Cow<'b, str>) -> Cow<'b, str> {
         let mut output = input;
 
-        for (regex, replacement) in &self.pairs {
-            let replacer = |captures: &regex::Captures| {
-                let mut result = String::new();
-                if let Some(prefix) = captures.name("prefix") {
-                    result.push_str(prefix.as_str())
-                }
-                result.push_str("${");
-                result.push_str(replacement);
-                result.push_str("}");
-                if let Some(suffix) = captures.name("suffix") {
-                    result.push_str(suffix.as_str())
-                }
-                result
-            };
+        for rule in &self.rules {
             match output {
                 Cow::Borrowed(borrowed) => {
-                    output = regex.replace_all(borrowed, replacer);
+                    output = self.replace_rule(rule, borrowed);
                 }
-                Cow::Owned(owned) => match regex.replace_all(&owned, replacer) {
+                Cow::Owned(owned) => match self.replace_rule(rule, &owned) {
                     Cow::Borrowed(borrowed) => {
                         debug_assert_eq!(borrowed, &owned);
                         output = Cow::Owned(owned);
@@ -221,31 +748,169 @@ impl ReplaceSet {
 
         output
     }
+
+    fn replace_rule<'h>(&self, rule: &ReplaceRule, haystack: &'h str) -> Cow<'h, str> {
+        let allowed = match &self.allowlist {
+            Some(allowlist) => allowlist.find_iter(haystack).map(|x| x.range()).collect(),
+            None => vec![],
+        };
+
+        let replacer = |captures: &regex::Captures| {
+            let matched = captures.get(0).unwrap();
+            let is_allowed = allowed
+                .iter()
+                .any(|x| x.start <= matched.start() && matched.end() <= x.end);
+            if is_allowed || (rule.whole_word && !is_whole_word(haystack, matched.range())) {
+                return matched.as_str().to_owned();
+            }
+
+            let mut result = String::new();
+            if let Some(prefix) = captures.name("prefix") {
+                result.push_str(prefix.as_str())
+            }
+            result.push_str("${");
+            result.push_str(rule.name);
+            result.push('}');
+            if let Some(suffix) = captures.name("suffix") {
+                result.push_str(suffix.as_str())
+            }
+            result
+        };
+
+        rule.regex.replace_all(haystack, replacer)
+    }
+}
+
+fn is_whole_word(haystack: &str, range: std::ops::Range<usize>) -> bool {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let matched = &haystack[range.clone()];
+    let joined_before = haystack[..range.start]
+        .chars()
+        .next_back()
+        .zip(matched.chars().next())
+        .is_some_and(|(before, first)| is_word_char(before) && is_word_char(first));
+    let joined_after = matched
+        .chars()
+        .next_back()
+        .zip(haystack[range.end..].chars().next())
+        .is_some_and(|(last, after)| is_word_char(last) && is_word_char(after));
+    !joined_before && !joined_after
+}
+
+/// Matches a path separator as it may appear in the log.
+/// `\\` is for JSON-escaped paths and `%2F` / `%5C` are for URL-encoded paths.
+const PATH_SEPARATOR_PATTERN: &str = r#"(?:[/\\]{1,2}|%2F|%5C)"#;
+
+/// Creates the pattern for one component of a path.
+/// On Windows, the component may be shown as 8.3 short name like `C:\Users\ANATAW~1`.
+fn path_component_pattern(component: &str) -> String {
+    let mut patterns = encoded_patterns(component);
+    if component.is_ascii() {
+        patterns.extend(short_name_pattern(component));
+    } else {
+        // short names for non-ascii names are generated from hash so we cannot derive them.
+        patterns.push(r"[0-9A-Z_!#$%&'()@^`{}\-]{1,6}~[0-9]{1,6}".to_owned());
+    }
+    join_alternatives(patterns)
+}
+
+/// Creates patterns for the text as-is, percent-encoded (as in URLs)
+/// and JSON-escaped with `\uXXXX`, for both NFC and NFD forms.
+/// NFD form is used in paths on macOS.
+fn encoded_patterns(text: &str) -> Vec<String> {
+    use std::fmt::Write;
+    use unicode_normalization::UnicodeNormalization;
+
+    let mut patterns = vec![];
+
+    for text in [
+        text.nfc().collect::<String>(),
+        text.nfd().collect::<String>(),
+    ] {
+        patterns.push(regex::escape(&text));
+
+        let mut percent_encoded = String::new();
+        for byte in text.bytes() {
+            if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+                percent_encoded.push(byte as char);
+            } else {
+                write!(percent_encoded, "%{byte:02X}").unwrap();
+            }
+        }
+        patterns.push(regex::escape(&percent_encoded));
+
+        let mut json_escaped = String::new();
+        for c in text.chars() {
+            if c.is_ascii() {
+                json_escaped.push(c);
+            } else {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    write!(json_escaped, "\\u{unit:04X}").unwrap();
+                }
+            }
+        }
+        patterns.push(regex::escape(&json_escaped));
+    }
+
+    patterns
+}
+
+/// Creates the pattern for 8.3 short name Windows generates for the name, like `ANATAW~1`.
+fn short_name_pattern(name: &str) -> Option<String> {
+    if is_valid_short_name(name) {
+        return None;
+    }
+
+    let base = name
+        .chars()
+        .filter(|&c| c != ' ' && c != '.')
+        .map(|c| match c {
+            '0'..='9' | 'A'..='Z' | 'a'..='z' => c.to_ascii_uppercase(),
+            _ => '_',
+        })
+        .take(6)
+        .collect::<String>();
+
+    if base.chars().all(|c| c == '_') {
+        // it's too generic to be replaced
+        return None;
+    }
+
+    Some(format!("{}~[0-9]{{1,6}}", regex::escape(&base)))
+}
+
+fn is_valid_short_name(name: &str) -> bool {
+    let (base, extension) = name.split_once('.').unwrap_or((name, ""));
+    let valid_char = |c: char| c.is_ascii_alphanumeric() || "_!#$%&'()@^`{}-~".contains(c);
+    (1..=8).contains(&base.len())
+        && extension.len() <= 3
+        && base.chars().all(valid_char)
+        && extension.chars().all(valid_char)
+}
+
+/// Joins patterns with `|`, longer first so that the longest variant is replaced.
+fn join_alternatives(mut patterns: Vec<String>) -> String {
+    patterns.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    patterns.dedup();
+    format!("(?:{})", patterns.join("|"))
 }
 
 pub type Result<T> = std::result::Result<T, ProcessRemoteError>;
 
 pub fn run_console_log_saver(pid: ProcessId, config: &ConsoleLogSaverConfig) -> Result<String> {
-    let buffer = process_remote::get_buffer(pid)?;
+    let buffer = process_remote::get_buffer(pid, &save_options(config))?;
 
-    let replacer = ReplaceSet::new(&config);
+    let replacer = ReplaceSet::new(config);
 
     let mut reader = TransferDataReader::new(buffer);
 
     let version = reader.read_i32()?;
-    if version != 1 {
+    if version != 4 {
         return Err(base_err("corrupted data"));
     }
 
     let mut cls_file_builder = ClsFileBuilder::new();
-    cls_file_builder.add_header(
-        "Vendor",
-        concat!(
-            "ConsoleLogSaver/",
-            env!("CARGO_PKG_VERSION"),
-            " (CLS-LLDB-RS)"
-        ),
-    );
+    cls_file_builder.add_header("Vendor", VENDOR);
 
     let unity_version = reader.read_string()?;
     cls_file_builder.add_header("Unity-Version", &unity_version);
@@ -255,6 +920,341 @@ pub fn run_console_log_saver(pid: ProcessId, config: &ConsoleLogSaverConfig) ->
         cls_file_builder.add_header("Editor-Platform", &os_description);
     }
 
+    append_hidden_data(&mut cls_file_builder, config);
+
+    let build_target = reader.read_string()?;
+    cls_file_builder.add_header("Build-Target", &build_target);
+
+    let current_directory = reader.read_string()?;
+
+    let editor_state = reader.read_i32()?;
+    let enter_play_mode_options = reader.read_i32()?;
+    let selection_type = reader.read_string()?;
+    let scene_count = reader.read_i32()?;
+    let mut scene_paths = Vec::with_capacity(scene_count.max(0) as usize);
+    for _ in 0..scene_count {
+        scene_paths.push(reader.read_string()?);
+    }
+    append_editor_state(&mut cls_file_builder, editor_state, enter_play_mode_options);
+    for path in scene_paths {
+        let path = if path.is_empty() {
+            "(untitled)".to_owned()
+        } else {
+            path
+        };
+        let path = replacer.replace_all(Cow::Owned(path));
+        cls_file_builder.add_header("Open-Scene", &single_line(&path));
+    }
+    if !selection_type.is_empty() {
+        cls_file_builder.add_header("Selection-Type", &single_line(&selection_type));
+    }
+
+    let console_cleared = reader.read_i32()? != 0;
+    let cursor = CaptureCursor {
+        count: reader.read_i32()?,
+        fingerprint: reader.read_u64()?,
+    };
+    cls_file_builder.add_header("Capture-Cursor", &cursor.to_string());
+    if let Some(since) = config.since {
+        cls_file_builder.add_header("Capture-Since", &since.to_string());
+        if console_cleared {
+            cls_file_builder.add_header("Console-Cleared", "true");
+        }
+    }
+
+    append_project_version(&mut cls_file_builder, &current_directory);
+    player_settings::append_player_settings(
+        &mut cls_file_builder,
+        &current_directory,
+        Some(&build_target),
+    );
+    append_upm(&mut cls_file_builder, &current_directory, &replacer);
+    append_upm_manifest(&mut cls_file_builder, &current_directory, &replacer);
+    append_vpm(&mut cls_file_builder, &current_directory);
+    if config.include_plugin_inventory {
+        plugin_inventory::append_plugin_inventory(
+            &mut cls_file_builder,
+            &current_directory,
+            &replacer,
+        );
+    }
+
+    let length: i32 = reader.read_i32()?;
+    let mut entries = Vec::with_capacity(length.max(0) as usize);
+    for _ in 0..length {
+        let message = reader.read_string()?;
+        let mode = reader.read_i32()?;
+        entries.push(LogEntry {
+            message,
+            mode,
+            repeat_count: 1,
+        });
+    }
+
+    // the compile errors are in the console if the internal API is not found
+    let mut compiler_messages = match CompilerMessage::read_all(&mut reader)? {
+        Some(messages) => messages,
+        None => entries
+            .iter()
+            .filter_map(CompilerMessage::from_log_entry)
+            .collect(),
+    };
+    // the messages of the last compilation are saved by the previous capture
+    // unless the compiler output is logged after that
+    let compiler_output_logged = entries
+        .iter()
+        .any(|x| CompilerMessage::from_log_entry(x).is_some());
+    if config.since.is_some() && !console_cleared && !compiler_output_logged {
+        compiler_messages.clear();
+    }
+
+    let entries = filter_entries(entries, config);
+    let saved_count = entries.iter().map(|x| x.repeat_count).sum::<usize>();
+    if saved_count < length as usize {
+        cls_file_builder.add_header(
+            "Omitted-Entries",
+            &format!("{}", length as usize - saved_count),
+        );
+    }
+
+    let mut cls_file_builder = cls_file_builder.begin_body();
+
+    if config.include_assembly_graph {
+        assembly_graph::append_assembly_graph(&mut cls_file_builder, &current_directory, &replacer);
+    }
+
+    compiler_message::append_compiler_messages(
+        &mut cls_file_builder,
+        &compiler_messages,
+        config,
+        &replacer,
+    );
+
+    for entry in entries {
+        let mode = entry.mode;
+        cls_file_builder.add_header("Mode", &format!("{mode}")); // TODO: transfer to name
+        cls_file_builder.add_header("Mode-Raw", &format!("{mode:08x}"));
+        if entry.repeat_count > 1 {
+            cls_file_builder.add_header("Repeat-Count", &format!("{}", entry.repeat_count));
+        }
+        cls_file_builder.add_content(
+            "log-element",
+            &replacer.replace_all(Cow::Borrowed(&entry.message)),
+        );
+    }
+
+    if config.editor_log_tail_kb.is_some() {
+        let process = find_unity_processes_in(&[current_directory.clone().into()])
+            .into_iter()
+            .find(|x| x.pid() == pid);
+        let log_path = match &process {
+            Some(process) => process.editor_log_path().map(ToOwned::to_owned),
+            None => editor_log::editor_log_paths().into_iter().next(),
+        };
+        let mut log_paths = Vec::from_iter(log_path);
+        // Editor-prev.log is only for the default Editor.log
+        if config.include_previous_editor_log {
+            let default_paths = editor_log::editor_log_paths();
+            if log_paths.first() == default_paths.first() {
+                log_paths.extend(default_paths.into_iter().nth(1));
+            }
+        }
+        for log_path in log_paths {
+            let max_bytes = config.editor_log_max_bytes();
+            append_editor_log_tail(&mut cls_file_builder, &log_path, max_bytes, &replacer);
+        }
+    }
+
+    if config.include_player_log {
+        let max_bytes = config.editor_log_max_bytes();
+        append_player_log(
+            &mut cls_file_builder,
+            &current_directory,
+            max_bytes,
+            &replacer,
+        );
+    }
+
+    Ok(cls_file_builder.build())
+}
+
+/// Applies the redaction to the existing CLS file.
+///
+/// The file created by old C# ConsoleLogSaver is also supported.
+/// Since the rules depend on this machine, the user name and user home of this machine will be hidden.
+pub fn redact_cls_file(
+    cls_file: &str,
+    config: &ConsoleLogSaverConfig,
+) -> std::result::Result<String, ClsParseError> {
+    let parsed = ClsFile::parse(cls_file)?;
+    let replacer = ReplaceSet::new(config);
+
+    let mut cls_file_builder = ClsFileBuilder::new();
+    let mut hidden_data = Vec::<&str>::new();
+    let mut weakened = Vec::<&str>::new();
+
+    for (name, value) in parsed.headers() {
+        if name.eq_ignore_ascii_case("separator") {
+            continue;
+        }
+        if name.eq_ignore_ascii_case("hidden-data") {
+            if !hidden_data.contains(&value.as_str()) {
+                hidden_data.push(value);
+            }
+            continue;
+        }
+        if name.eq_ignore_ascii_case("weakened-hidden-data") {
+            if !weakened.contains(&value.as_str()) {
+                weakened.push(value);
+            }
+            continue;
+        }
+        if config.hide_os_info && name.eq_ignore_ascii_case("editor-platform") {
+            continue;
+        }
+        let mut value = replacer.replace_all(Cow::Borrowed(value));
+        if name.eq_ignore_ascii_case("upm-dependency")
+            || name.eq_ignore_ascii_case("scoped-registry")
+        {
+            value = Cow::Owned(hide_url_credentials(&value).into_owned());
+        }
+        // the parser only splits lines at `\n` so the value may have `\r`
+        cls_file_builder.add_header(name, &single_line(&value));
+    }
+
+    cls_file_builder.add_header("Redacted-By", VENDOR);
+
+    for name in replacer.names().chain(["upm-git-credentials"]) {
+        if !hidden_data.contains(&name) {
+            hidden_data.push(name);
+        }
+    }
+    for name in hidden_data {
+        cls_file_builder.add_header("Hidden-Data", &single_line(name));
+    }
+    for name in config.weakened_rules() {
+        if !weakened.contains(&name) {
+            weakened.push(name);
+        }
+    }
+    for name in weakened {
+        cls_file_builder.add_header("Weakened-Hidden-Data", &single_line(name));
+    }
+
+    let mut cls_file_builder = cls_file_builder.begin_body();
+
+    for section in parsed.sections() {
+        for (name, value) in section.fields() {
+            if name.eq_ignore_ascii_case("content") || name.eq_ignore_ascii_case("separator") {
+                continue;
+            }
+            let value = replacer.replace_all(Cow::Borrowed(value));
+            cls_file_builder.add_header(name, &single_line(&value));
+        }
+        let content = replacer.replace_all(Cow::Borrowed(section.content()));
+        match section.content_type() {
+            Some(content_type) => cls_file_builder.add_content(content_type, &content),
+            None => cls_file_builder.add_untyped_content(&content),
+        };
+    }
+
+    Ok(cls_file_builder.build())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OfflineCaptureError {
+    #[error("no Editor.log for the project found")]
+    EditorLogNotFound,
+}
+
+/// Saves the log of the project not opened now, from `Editor.log` or `Editor-prev.log`.
+///
+/// The log is saved as one `editor-log` section since the log elements cannot be separated.
+pub fn run_offline_console_log_saver(
+    project_path: &std::path::Path,
+    config: &ConsoleLogSaverConfig,
+) -> std::result::Result<String, OfflineCaptureError> {
+    let max_bytes = config.editor_log_max_bytes();
+    let (log_path, content, truncated) =
+        editor_log::read_editor_log_for_project(project_path, max_bytes)
+            .ok_or(OfflineCaptureError::EditorLogNotFound)?;
+
+    let replacer = ReplaceSet::new(config);
+
+    let mut cls_file_builder = ClsFileBuilder::new();
+    cls_file_builder.add_header("Vendor", VENDOR);
+    if let Some(unity_version) = read_project_version(project_path) {
+        cls_file_builder.add_header("Unity-Version", &unity_version);
+    }
+    append_hidden_data(&mut cls_file_builder, config);
+    if let Some(file_name) = log_path.file_name() {
+        cls_file_builder.add_header("Capture-Source", &file_name.to_string_lossy());
+    }
+
+    let project_path = project_path.to_string_lossy();
+    append_project_version(&mut cls_file_builder, &project_path);
+    // the build target is not known without the editor
+    player_settings::append_player_settings(&mut cls_file_builder, &project_path, None);
+    append_upm(&mut cls_file_builder, &project_path, &replacer);
+    append_upm_manifest(&mut cls_file_builder, &project_path, &replacer);
+    append_vpm(&mut cls_file_builder, &project_path);
+    if config.include_plugin_inventory {
+        plugin_inventory::append_plugin_inventory(&mut cls_file_builder, &project_path, &replacer);
+    }
+
+    let mut cls_file_builder = cls_file_builder.begin_body();
+    if config.include_assembly_graph {
+        assembly_graph::append_assembly_graph(&mut cls_file_builder, &project_path, &replacer);
+    }
+    if truncated {
+        cls_file_builder.add_header("Truncated", "true");
+    }
+    cls_file_builder.add_content("editor-log", &replacer.replace_all(Cow::Borrowed(&content)));
+    if config.include_player_log {
+        append_player_log(&mut cls_file_builder, &project_path, max_bytes, &replacer);
+    }
+
+    Ok(cls_file_builder.build())
+}
+
+fn append_editor_log_tail(
+    cls_file_builder: &mut ClsBodyBuilder,
+    log_path: &std::path::Path,
+    max_bytes: u64,
+    replacer: &ReplaceSet,
+) {
+    let Some((content, truncated)) = editor_log::read_log_tail(log_path, max_bytes) else {
+        return;
+    };
+    if let Some(file_name) = log_path.file_name() {
+        cls_file_builder.add_header("Log-File", &file_name.to_string_lossy());
+    }
+    if truncated {
+        cls_file_builder.add_header("Truncated", "true");
+    }
+    cls_file_builder.add_content("editor-log", &replacer.replace_all(Cow::Borrowed(&content)));
+}
+
+fn append_player_log(
+    cls_file_builder: &mut ClsBodyBuilder,
+    cwd: &str,
+    max_bytes: u64,
+    replacer: &ReplaceSet,
+) {
+    let Some(log_path) = editor_log::player_log_path(std::path::Path::new(cwd)) else {
+        return;
+    };
+    let Some((content, truncated)) = editor_log::read_log_tail(&log_path, max_bytes) else {
+        return;
+    };
+    cls_file_builder.add_header("Log-File", "Player.log");
+    if truncated {
+        cls_file_builder.add_header("Truncated", "true");
+    }
+    cls_file_builder.add_content("player-log", &replacer.replace_all(Cow::Owned(content)));
+}
+
+fn append_hidden_data(cls_file_builder: &mut ClsHeadingBuilder, config: &ConsoleLogSaverConfig) {
     if config.hide_user_name {
         cls_file_builder.add_header("Hidden-Data", "user-name");
     }
@@ -265,34 +1265,77 @@ pub fn run_console_log_saver(pid: ProcessId, config: &ConsoleLogSaverConfig) ->
 
     cls_file_builder.add_header("Hidden-Data", "aws-access-key-id-param");
     cls_file_builder.add_header("Hidden-Data", "asset-url");
+    cls_file_builder.add_header("Hidden-Data", "upm-git-credentials");
 
     if config.hide_aws_upload_signature {
         cls_file_builder.add_header("Hidden-Data", "signature-param");
     }
 
-    let build_target = reader.read_string()?;
-    cls_file_builder.add_header("Build-Target", &build_target);
+    let mut custom_rule_names = config
+        .custom_rules
+        .iter()
+        .map(|x| x.name())
+        .collect::<Vec<_>>();
+    custom_rule_names.dedup();
+    for name in custom_rule_names {
+        cls_file_builder.add_header("Hidden-Data", name);
+    }
 
-    let current_directory = reader.read_string()?;
+    for name in config.weakened_rules() {
+        cls_file_builder.add_header("Weakened-Hidden-Data", name);
+    }
+}
 
-    append_upm(&mut cls_file_builder, &current_directory, &replacer);
-    append_vpm(&mut cls_file_builder, &current_directory);
+// the flags of editor_state in cls-attach-lib
+const EDITOR_STATE_PLAYING: i32 = 1 << 0;
+const EDITOR_STATE_PAUSED: i32 = 1 << 1;
+const EDITOR_STATE_WILL_CHANGE_PLAYMODE: i32 = 1 << 2;
+const EDITOR_STATE_COMPILING: i32 = 1 << 3;
+const EDITOR_STATE_UPDATING: i32 = 1 << 4;
 
-    let mut cls_file_builder = cls_file_builder.begin_body();
+const ENTER_PLAY_MODE_OPTIONS_DISABLE_DOMAIN_RELOAD: i32 = 1 << 0;
 
-    let length: i32 = reader.read_i32()?;
-    for _ in 0..length {
-        let log_message = reader.read_string()?;
-        let mode = reader.read_i32()?;
-        cls_file_builder.add_header("Mode", &format!("{mode}")); // TODO: transfer to name
-        cls_file_builder.add_header("Mode-Raw", &format!("{mode:08x}"));
-        cls_file_builder.add_content(
-            "log-element",
-            &replacer.replace_all(Cow::Borrowed(&log_message)),
-        );
+fn append_editor_state(
+    cls_file_builder: &mut ClsHeadingBuilder,
+    editor_state: i32,
+    enter_play_mode_options: i32,
+) {
+    let playing = editor_state & EDITOR_STATE_PLAYING != 0;
+    let will_change = editor_state & EDITOR_STATE_WILL_CHANGE_PLAYMODE != 0;
+    let play_mode = match (playing, will_change) {
+        (false, false) => "edit",
+        (false, true) => "entering",
+        (true, false) => "exiting",
+        (true, true) if editor_state & EDITOR_STATE_PAUSED != 0 => "paused",
+        (true, true) => "playing",
+    };
+    cls_file_builder.add_header("Play-Mode", play_mode);
+
+    let compiling = editor_state & EDITOR_STATE_COMPILING != 0;
+    cls_file_builder.add_header("Editor-Compiling", &compiling.to_string());
+    let updating = editor_state & EDITOR_STATE_UPDATING != 0;
+    cls_file_builder.add_header("Editor-Updating", &updating.to_string());
+
+    // -1 for unity without Enter Play Mode Options
+    if enter_play_mode_options >= 0 {
+        let domain_reload =
+            if enter_play_mode_options & ENTER_PLAY_MODE_OPTIONS_DISABLE_DOMAIN_RELOAD != 0 {
+                "disabled"
+            } else {
+                "enabled"
+            };
+        cls_file_builder.add_header("Domain-Reload", domain_reload);
     }
+}
 
-    Ok(cls_file_builder.build())
+/// Creates `SaveOptions` for cls-attach-lib
+fn save_options(config: &ConsoleLogSaverConfig) -> Vec<u8> {
+    let mut options = Vec::new();
+    let (count, fingerprint) = config.since.map_or((0, 0), |x| (x.count, x.fingerprint));
+    options.extend_from_slice(&count.to_ne_bytes());
+    options.extend_from_slice(&0i32.to_ne_bytes()); // reserved
+    options.extend_from_slice(&fingerprint.to_ne_bytes());
+    options
 }
 
 struct TransferDataReader {
@@ -312,6 +1355,12 @@ impl TransferDataReader {
             .map_err(|_| base_err("failed to read i32"))
     }
 
+    fn read_u64(&mut self) -> Result<u64> {
+        self.reader
+            .read_u64::<NativeEndian>()
+            .map_err(|_| base_err("failed to read u64"))
+    }
+
     fn read_string(&mut self) -> Result<String> {
         let char_length = self.read_i32()?;
         let mut buffer = vec![0u16; char_length as usize];
@@ -322,6 +1371,85 @@ impl TransferDataReader {
     }
 }
 
+enum UpmDependencyType {
+    NpmRemote,
+    HttpsGit,
+    SshGit,
+    GitGit,
+    FileGit,
+    FileRelative,
+    FileAbsolute,
+}
+
+impl UpmDependencyType {
+    fn detect_from_version(version: &str) -> Self {
+        if version.starts_with("file://") || version.contains(".git") || version.starts_with("git+")
+        {
+            // it's some git URLs
+            let version = version.strip_prefix("git+").unwrap_or(version);
+
+            if version.starts_with("https:") {
+                return UpmDependencyType::HttpsGit;
+            }
+            if version.starts_with("ssh:") {
+                return UpmDependencyType::SshGit;
+            }
+            if version.starts_with("file:") {
+                return UpmDependencyType::FileGit;
+            }
+            if version.starts_with("git:") {
+                return UpmDependencyType::GitGit;
+            }
+        }
+
+        if let Some(path) = version.strip_prefix("file:") {
+            // it's some file URLs
+            let path = std::path::Path::new(path);
+            if path.has_root() {
+                return UpmDependencyType::FileAbsolute;
+            } else {
+                return UpmDependencyType::FileRelative;
+            }
+        }
+
+        UpmDependencyType::NpmRemote
+    }
+
+    fn is_git(&self) -> bool {
+        matches!(
+            self,
+            UpmDependencyType::GitGit
+                | UpmDependencyType::FileGit
+                | UpmDependencyType::HttpsGit
+                | UpmDependencyType::SshGit
+        )
+    }
+}
+
+/// Hides the personal information in the version of the UPM dependency.
+fn hide_upm_version<'a>(version: &'a str, replacer: &ReplaceSet) -> Cow<'a, str> {
+    match UpmDependencyType::detect_from_version(version) {
+        UpmDependencyType::NpmRemote => {
+            // npm versions are just version numbers
+            Cow::Borrowed(version)
+        }
+        UpmDependencyType::HttpsGit | UpmDependencyType::SshGit | UpmDependencyType::GitGit => {
+            // Those are remote, so it's very unlikely to include personal information
+            // but private packages may be fetched with credentials in URL
+            hide_url_credentials(version)
+        }
+        UpmDependencyType::FileGit | UpmDependencyType::FileAbsolute => {
+            // file git is mostly absolute path
+            // an absolute path may include user home
+            replacer.replace_all(Cow::Borrowed(version))
+        }
+        UpmDependencyType::FileRelative => {
+            // relative path mostly doesn't include user home
+            Cow::Borrowed(version)
+        }
+    }
+}
+
 fn append_upm(builder: &mut ClsHeadingBuilder, cwd: &str, replacer: &ReplaceSet) {
     #[derive(Deserialize)]
     struct PackageLock {
@@ -333,63 +1461,6 @@ fn append_upm(builder: &mut ClsHeadingBuilder, cwd: &str, replacer: &ReplaceSet)
         hash: Option<String>,
     }
 
-    enum UpmDependencyType {
-        NpmRemote,
-        HttpsGit,
-        SshGit,
-        GitGit,
-        FileGit,
-        FileRelative,
-        FileAbsolute,
-    }
-
-    impl UpmDependencyType {
-        fn detect_from_version(version: &str) -> Self {
-            if version.starts_with("file://")
-                || version.contains(".git")
-                || version.starts_with("git+")
-            {
-                // it's some git URLs
-                let version = version.strip_prefix("git+").unwrap_or(version);
-
-                if version.starts_with("https:") {
-                    return UpmDependencyType::HttpsGit;
-                }
-                if version.starts_with("ssh:") {
-                    return UpmDependencyType::SshGit;
-                }
-                if version.starts_with("file:") {
-                    return UpmDependencyType::FileGit;
-                }
-                if version.starts_with("git:") {
-                    return UpmDependencyType::GitGit;
-                }
-            }
-
-            if let Some(path) = version.strip_prefix("file:") {
-                // it's some file URLs
-                let path = std::path::Path::new(path);
-                if path.has_root() {
-                    return UpmDependencyType::FileAbsolute;
-                } else {
-                    return UpmDependencyType::FileRelative;
-                }
-            }
-
-            UpmDependencyType::NpmRemote
-        }
-
-        fn is_git(&self) -> bool {
-            matches!(
-                self,
-                UpmDependencyType::GitGit
-                    | UpmDependencyType::FileGit
-                    | UpmDependencyType::HttpsGit
-                    | UpmDependencyType::SshGit
-            )
-        }
-    }
-
     let package_lock = std::path::Path::new(cwd).join("Packages/packages-lock.json");
     let Ok(package_lock) = std::fs::read(&package_lock) else {
         return;
@@ -397,39 +1468,199 @@ fn append_upm(builder: &mut ClsHeadingBuilder, cwd: &str, replacer: &ReplaceSet)
     let Ok(package_lock) = serde_json::from_slice::<PackageLock>(&package_lock) else {
         return;
     };
+    let packages_dir = std::path::Path::new(cwd).join("Packages");
+    let mut local_packages = Vec::new();
     for (dependency, lock_info) in package_lock.dependencies {
         if let Some(version) = lock_info.version {
-            let mut version = Cow::Borrowed(version.as_str());
             let dependency_type = UpmDependencyType::detect_from_version(&version);
-            match dependency_type {
-                UpmDependencyType::NpmRemote
-                | UpmDependencyType::HttpsGit
-                | UpmDependencyType::SshGit
-                | UpmDependencyType::GitGit => {
-                    // Those are remote, so it's very unlikely to include personal information
-                }
-                UpmDependencyType::FileGit | UpmDependencyType::FileAbsolute => {
-                    // file git is mostly absolute path
-                    // an absolute path may include user home
-                    let replaced = replacer.replace_all(version);
-                    version = replaced;
-                }
-                UpmDependencyType::FileRelative => {
-                    // relative path mostly doesn't include user home
-                }
+            if let UpmDependencyType::FileRelative | UpmDependencyType::FileAbsolute =
+                dependency_type
+            {
+                // relative paths are relative to the Packages folder
+                let path = version.strip_prefix("file:").unwrap_or(&version);
+                local_packages.push(packages_dir.join(path));
             }
+            let version = hide_upm_version(&version, replacer);
             let mut version = format!("{dependency}@{version}");
             if dependency_type.is_git() {
                 if let Some(hash) = lock_info.hash {
-                    version.push_str(":");
+                    version.push(':');
                     version.push_str(&hash);
                 }
             }
-            builder.add_header("Upm-Dependency", &version);
+            builder.add_header("Upm-Dependency", &single_line(&version));
+        }
+    }
+
+    append_upm_local_packages(builder, &packages_dir, local_packages, replacer);
+}
+
+/// Appends the name, version and display name in `package.json` of embedded and local packages,
+/// since the version in `packages-lock.json` is just a path for them.
+fn append_upm_local_packages(
+    builder: &mut ClsHeadingBuilder,
+    packages_dir: &std::path::Path,
+    mut local_packages: Vec<std::path::PathBuf>,
+    replacer: &ReplaceSet,
+) {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct PackageJson {
+        name: String,
+        version: Option<String>,
+        display_name: Option<String>,
+    }
+
+    // embedded packages not resolved yet are not in the lock file
+    if let Ok(entries) = std::fs::read_dir(packages_dir) {
+        local_packages.extend(entries.filter_map(|x| x.ok()).map(|x| x.path()));
+    }
+
+    let mut reported = std::collections::HashSet::new();
+    for path in local_packages {
+        let path = std::fs::canonicalize(&path).unwrap_or(path);
+        if !reported.insert(path.clone()) {
+            continue;
+        }
+        let Ok(package_json) = std::fs::read(path.join("package.json")) else {
+            continue;
+        };
+        let Ok(package_json) = serde_json::from_slice::<PackageJson>(&package_json) else {
+            continue;
+        };
+
+        let git_state = git_state::read_git_state(&path);
+        let source = if git_state.is_some() {
+            "git-checkout"
+        } else if path.parent() == std::fs::canonicalize(packages_dir).ok().as_deref() {
+            "embedded"
+        } else {
+            "local"
+        };
+
+        let name = package_json.name;
+        let version = package_json.version.unwrap_or_default();
+        let mut value = format!("{name}@{version} {source}");
+        if let Some(display_name) = package_json.display_name {
+            value.push(' ');
+            value.push_str(&display_name);
+        }
+        builder.add_header("Upm-Local-Package", &single_line(&value));
+
+        if let Some(git_state) = git_state {
+            let commit = git_state.commit.as_deref().unwrap_or("(no commit)");
+            let branch = git_state.branch.as_deref().unwrap_or("(detached)");
+            let state = match git_state.dirty {
+                Some(true) => "dirty",
+                Some(false) => "clean",
+                None => "unknown",
+            };
+            // the branch name may contain the user name
+            let value = format!("{name} git {commit} {branch} {state}");
+            let value = replacer.replace_all(Cow::Owned(value));
+            builder.add_header("Package-Vcs", &single_line(&value));
         }
     }
 }
 
+/// Appends the revision of the editor in `ProjectSettings/ProjectVersion.txt`
+fn append_project_version(builder: &mut ClsHeadingBuilder, cwd: &str) {
+    let path = std::path::Path::new(cwd).join("ProjectSettings/ProjectVersion.txt");
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return;
+    };
+    // like `2022.3.22f1 (887be4894c44)`
+    let Some(with_revision) = find_yaml_value(&content, "m_EditorVersionWithRevision") else {
+        return;
+    };
+    let Some((_, revision)) = with_revision.split_once('(') else {
+        return;
+    };
+    let revision = revision.trim_end_matches(')').trim();
+    if !revision.is_empty() {
+        builder.add_header("Editor-Revision", revision);
+    }
+}
+
+fn append_upm_manifest(builder: &mut ClsHeadingBuilder, cwd: &str, replacer: &ReplaceSet) {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Manifest {
+        #[serde(default)]
+        dependencies: std::collections::BTreeMap<String, String>,
+        #[serde(default)]
+        testables: Vec<String>,
+        #[serde(default)]
+        scoped_registries: Vec<ScopedRegistry>,
+    }
+    #[derive(Deserialize)]
+    struct ScopedRegistry {
+        name: Option<String>,
+        url: Option<String>,
+        #[serde(default)]
+        scopes: Vec<String>,
+    }
+
+    let manifest = std::path::Path::new(cwd).join("Packages/manifest.json");
+    let Ok(manifest) = std::fs::read(&manifest) else {
+        return;
+    };
+    let Ok(manifest) = serde_json::from_slice::<Manifest>(&manifest) else {
+        return;
+    };
+    // the versions requested in manifest.json may differ from the resolved Upm-Dependency
+    for (dependency, version) in manifest.dependencies {
+        let version = hide_upm_version(&version, replacer);
+        let value = format!("{dependency}@{version}");
+        builder.add_header("Upm-Direct-Dependency", &single_line(&value));
+    }
+    for testable in manifest.testables {
+        builder.add_header("Upm-Testable", &single_line(&testable));
+    }
+    for registry in manifest.scoped_registries {
+        let Some(url) = registry.url else { continue };
+        let name = registry.name.unwrap_or_default();
+        let url = hide_url_credentials(&url);
+        let scopes = registry.scopes.join(",");
+        let value = format!("{name} <{url}> {scopes}");
+        builder.add_header("Scoped-Registry", &single_line(&value));
+    }
+}
+
+/// Hides userinfo and token-like query parameters in the URL.
+/// Since `git@` is the common user name for ssh, it's not hidden.
+fn hide_url_credentials(url: &str) -> Cow<'_, str> {
+    static USER_INFO: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+        Regex::new(r"(?<prefix>\b[a-zA-Z][a-zA-Z0-9+.\-]*://)(?<userinfo>[^@/?#\s]*)@").unwrap()
+    });
+    static TOKEN_PARAM: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+        regex::RegexBuilder::new(
+            r"(?<prefix>[?&](?:token|access_token|private_token|auth|password|key|secret)=)[^&#]*",
+        )
+        .case_insensitive(true)
+        .build()
+        .unwrap()
+    });
+
+    let mut url = USER_INFO.replace_all(url, |captures: &regex::Captures| {
+        if &captures["userinfo"] == "git" {
+            captures[0].to_owned()
+        } else {
+            format!("{}${{upm-git-credentials}}@", &captures["prefix"])
+        }
+    });
+
+    if TOKEN_PARAM.is_match(&url) {
+        url = Cow::Owned(
+            TOKEN_PARAM
+                .replace_all(&url, "${prefix}$${upm-git-credentials}")
+                .into_owned(),
+        );
+    }
+
+    url
+}
+
 fn append_vpm(builder: &mut ClsHeadingBuilder, cwd: &str) {
     #[derive(Deserialize)]
     struct PackageLock {
@@ -449,7 +1680,8 @@ fn append_vpm(builder: &mut ClsHeadingBuilder, cwd: &str) {
     };
     for (dependency, lock_info) in package_lock.locked {
         if let Some(version) = lock_info.version {
-            builder.add_header("Vpm-Dependency", &format!("{dependency}@{version}"));
+            let value = format!("{dependency}@{version}");
+            builder.add_header("Vpm-Dependency", &single_line(&value));
         }
     }
 }
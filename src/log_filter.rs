@@ -0,0 +1,109 @@
+use crate::ConsoleLogSaverConfig;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+// see UnityEditor.ConsoleWindow.Mode
+const MODE_ERROR: i32 = 1 << 0;
+const MODE_ASSERT: i32 = 1 << 1;
+const MODE_FATAL: i32 = 1 << 4;
+const MODE_ASSET_IMPORT_ERROR: i32 = 1 << 6;
+const MODE_ASSET_IMPORT_WARNING: i32 = 1 << 7;
+const MODE_SCRIPTING_ERROR: i32 = 1 << 8;
+const MODE_SCRIPTING_WARNING: i32 = 1 << 9;
+pub(crate) const MODE_SCRIPT_COMPILE_ERROR: i32 = 1 << 11;
+pub(crate) const MODE_SCRIPT_COMPILE_WARNING: i32 = 1 << 12;
+const MODE_SCRIPTING_EXCEPTION: i32 = 1 << 17;
+const MODE_GRAPH_COMPILE_ERROR: i32 = 1 << 20;
+const MODE_SCRIPTING_ASSERTION: i32 = 1 << 21;
+
+const ERROR_MODES: i32 = MODE_FATAL
+    | MODE_ASSERT
+    | MODE_ERROR
+    | MODE_SCRIPTING_ERROR
+    | MODE_ASSET_IMPORT_ERROR
+    | MODE_SCRIPT_COMPILE_ERROR
+    | MODE_GRAPH_COMPILE_ERROR
+    | MODE_SCRIPTING_ASSERTION
+    | MODE_SCRIPTING_EXCEPTION;
+const WARNING_MODES: i32 =
+    MODE_SCRIPT_COMPILE_WARNING | MODE_SCRIPTING_WARNING | MODE_ASSET_IMPORT_WARNING;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LogSeverity {
+    Log,
+    Warning,
+    Error,
+}
+
+impl LogSeverity {
+    /// Gets the severity the same way as the console window shows the icon.
+    pub fn from_mode(mode: i32) -> LogSeverity {
+        if mode & ERROR_MODES != 0 {
+            LogSeverity::Error
+        } else if mode & WARNING_MODES != 0 {
+            LogSeverity::Warning
+        } else {
+            LogSeverity::Log
+        }
+    }
+}
+
+pub(crate) struct LogEntry {
+    pub message: String,
+    pub mode: i32,
+    pub repeat_count: usize,
+}
+
+/// Filters the entries with the config.
+/// The entries should be in the order of the console, the oldest first.
+pub(crate) fn filter_entries(
+    entries: Vec<LogEntry>,
+    config: &ConsoleLogSaverConfig,
+) -> Vec<LogEntry> {
+    let mut filtered = Vec::<LogEntry>::with_capacity(entries.len());
+    let mut first_indices = HashMap::<(i32, String), usize>::new();
+
+    for entry in entries {
+        let included = match LogSeverity::from_mode(entry.mode) {
+            LogSeverity::Log => config.include_log,
+            LogSeverity::Warning => config.include_warning,
+            LogSeverity::Error => config.include_error,
+        };
+        if !included {
+            continue;
+        }
+        if let Some(include) = &config.include_regex {
+            if !include.is_match(&entry.message) {
+                continue;
+            }
+        }
+        if let Some(exclude) = &config.exclude_regex {
+            if exclude.is_match(&entry.message) {
+                continue;
+            }
+        }
+
+        if config.deduplicate {
+            // like collapse of the console window, we keep the first one
+            match first_indices.entry((entry.mode, entry.message.clone())) {
+                Entry::Occupied(index) => {
+                    filtered[*index.get()].repeat_count += entry.repeat_count;
+                    continue;
+                }
+                Entry::Vacant(vacant) => {
+                    vacant.insert(filtered.len());
+                }
+            }
+        }
+
+        filtered.push(entry);
+    }
+
+    if let Some(max_entries) = config.max_entries {
+        if filtered.len() > max_entries {
+            filtered.drain(..filtered.len() - max_entries);
+        }
+    }
+
+    filtered
+}
@@ -0,0 +1,203 @@
+use crate::cls_file::ClsHeadingBuilder;
+use crate::find_yaml_value;
+use std::path::Path;
+
+/// Appends the scripting define symbols, scripting backend, API compatibility level and graphics APIs
+/// of the build target, and the color space in `ProjectSettings/ProjectSettings.asset`.
+///
+/// The settings per platform are skipped if the build target is unknown.
+pub(crate) fn append_player_settings(
+    builder: &mut ClsHeadingBuilder,
+    cwd: &str,
+    build_target: Option<&str>,
+) {
+    let path = Path::new(cwd).join("ProjectSettings/ProjectSettings.asset");
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return;
+    };
+
+    if let Some(platform) = build_target.and_then(BuildTargetPlatform::from_build_target) {
+        let defines = find_platform_value(&content, "scriptingDefineSymbols", &platform);
+        if let Some(defines) = defines.filter(|x| !x.is_empty()) {
+            builder.add_header("Scripting-Define-Symbols", defines);
+        }
+
+        let backend = find_platform_value(&content, "scriptingBackend", &platform);
+        if let Some(backend) = backend {
+            builder.add_header("Scripting-Backend", scripting_backend_name(backend));
+        }
+
+        let api_level =
+            find_platform_value(&content, "apiCompatibilityLevelPerPlatform", &platform)
+                .or_else(|| find_yaml_value(&content, "apiCompatibilityLevel"));
+        if let Some(api_level) = api_level {
+            builder.add_header("Api-Compatibility-Level", api_level_name(api_level));
+        }
+
+        if let Some(apis) = find_graphics_apis(&content, platform.graphics_target) {
+            builder.add_header("Graphics-Apis", &apis);
+        }
+    }
+
+    let color_space = match find_yaml_value(&content, "m_ActiveColorSpace") {
+        Some("0") => Some("Gamma"),
+        Some("1") => Some("Linear"),
+        _ => None,
+    };
+    if let Some(color_space) = color_space {
+        builder.add_header("Color-Space", color_space);
+    }
+}
+
+/// The names of the platform used in `ProjectSettings.asset`.
+struct BuildTargetPlatform {
+    /// The name of `BuildTargetGroup` or `NamedBuildTarget`
+    group: &'static str,
+    /// The value of `BuildTargetGroup` used by old unity
+    group_id: &'static str,
+    /// The name used in `m_BuildTargetGraphicsAPIs`
+    graphics_target: &'static str,
+}
+
+impl BuildTargetPlatform {
+    fn from_build_target(build_target: &str) -> Option<Self> {
+        let (group, group_id, graphics_target) = match build_target {
+            "StandaloneWindows" | "StandaloneWindows64" => {
+                ("Standalone", "1", "WindowsStandaloneSupport")
+            }
+            "StandaloneOSX" => ("Standalone", "1", "MacStandaloneSupport"),
+            "StandaloneLinux64" => ("Standalone", "1", "LinuxStandaloneSupport"),
+            "iOS" => ("iPhone", "4", "iOSSupport"),
+            "Android" => ("Android", "7", "AndroidPlayer"),
+            "WebGL" => ("WebGL", "13", "WebGLSupport"),
+            "WSAPlayer" => ("Metro", "14", "MetroSupport"),
+            "PS4" => ("PS4", "19", "PS4Player"),
+            "XboxOne" => ("XboxOne", "21", "XboxOnePlayer"),
+            "tvOS" => ("tvOS", "25", "AppleTVSupport"),
+            "Switch" => ("Switch", "27", "Switch"),
+            _ => return None,
+        };
+        Some(Self {
+            group,
+            group_id,
+            graphics_target,
+        })
+    }
+}
+
+/// Finds the value for the platform in the map like `scriptingDefineSymbols`.
+/// Old unity uses the number of `BuildTargetGroup` as the key.
+fn find_platform_value<'a>(
+    content: &'a str,
+    key: &str,
+    platform: &BuildTargetPlatform,
+) -> Option<&'a str> {
+    find_yaml_map(content, key).find_map(|(name, value)| {
+        (name == platform.group || name == platform.group_id).then_some(value)
+    })
+}
+
+/// Finds the entries of the map like
+/// ```yaml
+///   key:
+///     name: value
+/// ```
+fn find_yaml_map<'a>(content: &'a str, key: &str) -> impl Iterator<Item = (&'a str, &'a str)> {
+    let mut lines = content.lines();
+    let indent = lines
+        .by_ref()
+        .find(|line| line.trim_start().strip_prefix(key) == Some(":"))
+        .map(|line| line.len() - line.trim_start().len());
+
+    lines
+        .take_while(move |line| {
+            let line_indent = line.len() - line.trim_start().len();
+            indent.is_some_and(|indent| line_indent > indent)
+        })
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            Some((name.trim(), value.trim()))
+        })
+}
+
+/// Reads the graphics APIs set manually for the platform.
+/// `m_APIs` is the hex of `GraphicsDeviceType` values in 32-bit little endian.
+fn find_graphics_apis(content: &str, graphics_target: &str) -> Option<String> {
+    let mut lines = content.lines();
+    let indent = lines
+        .by_ref()
+        .find(|line| line.trim() == "m_BuildTargetGraphicsAPIs:")
+        .map(|line| line.len() - line.trim_start().len())?;
+    // the list items are at the same indent as the key
+    let entry = format!("- m_BuildTarget: {graphics_target}");
+    let lines = lines
+        .take_while(|line| {
+            let line_indent = line.len() - line.trim_start().len();
+            line_indent > indent || line.trim_start().starts_with('-')
+        })
+        .map(str::trim)
+        .skip_while(|line| *line != entry)
+        .skip(1)
+        .take_while(|line| !line.starts_with('-'));
+
+    let mut apis = None;
+    let mut automatic = false;
+    for line in lines {
+        if let Some(value) = line.strip_prefix("m_APIs:") {
+            apis = Some(value.trim());
+        } else if let Some(value) = line.strip_prefix("m_Automatic:") {
+            automatic = value.trim() == "1";
+        }
+    }
+    if automatic {
+        return Some("auto".to_owned());
+    }
+
+    let apis = apis?;
+    let names = (0..apis.len() / 8)
+        .filter_map(|index| {
+            let bytes = apis.get(index * 8..index * 8 + 8)?;
+            let value = u32::from_str_radix(bytes, 16).ok()?.swap_bytes();
+            Some(graphics_device_type_name(value))
+        })
+        .collect::<Vec<_>>();
+    Some(names.join(","))
+}
+
+fn scripting_backend_name(value: &str) -> &str {
+    match value {
+        "0" => "Mono",
+        "1" => "IL2CPP",
+        "2" => "WinRTDotNET",
+        value => value,
+    }
+}
+
+fn api_level_name(value: &str) -> &str {
+    match value {
+        "1" => ".NET 2.0",
+        "2" => ".NET 2.0 Subset",
+        "3" => ".NET Framework",
+        "6" => ".NET Standard",
+        value => value,
+    }
+}
+
+fn graphics_device_type_name(value: u32) -> String {
+    let name = match value {
+        2 => "Direct3D11",
+        4 => "Null",
+        8 => "OpenGLES2",
+        11 => "OpenGLES3",
+        16 => "Metal",
+        17 => "OpenGLCore",
+        18 => "Direct3D12",
+        21 => "Vulkan",
+        22 => "Switch",
+        23 => "XboxOneD3D12",
+        24 => "GameCoreXboxOne",
+        25 => "GameCoreXboxSeries",
+        value => return value.to_string(),
+    };
+    name.to_owned()
+}
@@ -0,0 +1,184 @@
+use crate::assembly_metadata::read_assembly_info;
+use crate::cls_file::{single_line, ClsHeadingBuilder};
+use crate::project_files::{extension, read_json, read_meta, ProjectFiles};
+use crate::ReplaceSet;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+enum InventoryEntry {
+    Plugin {
+        native: bool,
+        version: Option<String>,
+        platforms: Option<Vec<String>>,
+    },
+    AssemblyDefinition,
+}
+
+struct FoundFile {
+    path: PathBuf,
+    /// The name of the assembly defined by the asmdef or the managed DLL
+    assembly_name: Option<String>,
+    entry: InventoryEntry,
+}
+
+/// Appends the native plugins, managed DLLs and assembly definitions in `Assets` and packages.
+///
+/// The paths in the project are relative to the project.
+pub(crate) fn append_plugin_inventory(
+    builder: &mut ClsHeadingBuilder,
+    cwd: &str,
+    replacer: &ReplaceSet,
+) {
+    let project_files = ProjectFiles::new(Path::new(cwd));
+
+    let mut found = Vec::new();
+    project_files.walk(|path, is_dir| match extension(&path).as_deref() {
+        // macOS plugins are bundle directories
+        Some("bundle") => {
+            found.push(native_plugin(path));
+            false
+        }
+        _ if is_dir => true,
+        Some("so") | Some("dylib") => {
+            found.push(native_plugin(path));
+            false
+        }
+        Some("dll") => {
+            found.push(dll_plugin(path));
+            false
+        }
+        Some("asmdef") => {
+            #[derive(Deserialize)]
+            struct AssemblyDefinition {
+                name: String,
+            }
+            let assembly_name = read_json::<AssemblyDefinition>(&path).map(|x| x.name);
+            found.push(FoundFile {
+                path,
+                assembly_name,
+                entry: InventoryEntry::AssemblyDefinition,
+            });
+            false
+        }
+        _ => false,
+    });
+    found.sort_by(|a, b| a.path.cmp(&b.path));
+
+    for file in &found {
+        let path = project_files.display_path(&file.path, replacer);
+        match &file.entry {
+            InventoryEntry::Plugin {
+                native,
+                version,
+                platforms,
+            } => {
+                let kind = if *native { "native" } else { "managed" };
+                let version = version.as_deref().unwrap_or("-");
+                let platforms = platforms.as_ref().map(|x| x.join(","));
+                let platforms = platforms
+                    .as_deref()
+                    .filter(|x| !x.is_empty())
+                    .unwrap_or("-");
+                builder.add_header("Plugin", &format!("{kind} {version} {platforms} {path}"));
+            }
+            InventoryEntry::AssemblyDefinition => {
+                let name = file.assembly_name.as_deref().unwrap_or("-");
+                let name = single_line(name);
+                builder.add_header("Assembly-Definition", &format!("{name} {path}"));
+            }
+        }
+    }
+
+    // asmdefs and DLLs cannot define the same assembly
+    let mut by_name = BTreeMap::<&str, Vec<&FoundFile>>::new();
+    for file in &found {
+        if let Some(name) = &file.assembly_name {
+            by_name.entry(name).or_default().push(file);
+        }
+    }
+    for (name, files) in by_name {
+        if files.len() < 2 {
+            continue;
+        }
+        for file in files {
+            let path = project_files.display_path(&file.path, replacer);
+            let name = single_line(name);
+            builder.add_header("Duplicate-Assembly", &format!("{name} {path}"));
+        }
+    }
+}
+
+fn dll_plugin(path: PathBuf) -> FoundFile {
+    let info = std::fs::read(&path)
+        .ok()
+        .and_then(|x| read_assembly_info(&x));
+    let platforms = read_plugin_platforms(&path);
+    match info {
+        Some(info) => {
+            let (major, minor, build, revision) = info.version;
+            FoundFile {
+                path,
+                assembly_name: Some(info.name),
+                entry: InventoryEntry::Plugin {
+                    native: false,
+                    version: Some(format!("{major}.{minor}.{build}.{revision}")),
+                    platforms,
+                },
+            }
+        }
+        None => native_plugin(path),
+    }
+}
+
+fn native_plugin(path: PathBuf) -> FoundFile {
+    let platforms = read_plugin_platforms(&path);
+    FoundFile {
+        path,
+        assembly_name: None,
+        entry: InventoryEntry::Plugin {
+            native: true,
+            version: None,
+            platforms,
+        },
+    }
+}
+
+/// Reads the enabled platforms in `platformData` of the `PluginImporter` in the `.meta` file.
+///
+/// Platforms are like `Any`, `Editor` or `Standalone/Win64`.
+fn read_plugin_platforms(path: &Path) -> Option<Vec<String>> {
+    let meta = read_meta(path)?;
+    if !meta.contains("PluginImporter:") {
+        return None;
+    }
+
+    let mut platforms = Vec::new();
+    let mut lines = meta.lines().skip_while(|x| x.trim() != "platformData:");
+    let mut current = None;
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if line == "first:" || line == "- first:" {
+            // the next line is like `Standalone: Win64` or `Any: `
+            let Some((category, platform)) = lines.next().and_then(|x| x.split_once(':')) else {
+                continue;
+            };
+            let (category, platform) = (category.trim(), platform.trim());
+            current = Some(if platform.is_empty() || platform == category {
+                category.to_owned()
+            } else if category.is_empty() {
+                platform.to_owned()
+            } else {
+                format!("{category}/{platform}")
+            });
+        } else if let Some(enabled) = line.strip_prefix("enabled:") {
+            if let Some(platform) = current.take() {
+                if enabled.trim() == "1" {
+                    platforms.push(platform);
+                }
+            }
+        }
+    }
+
+    Some(platforms)
+}
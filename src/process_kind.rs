@@ -0,0 +1,85 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// The kind of the Unity process.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum UnityProcessKind {
+    /// The editor, including the editor in batchmode.
+    Editor,
+    /// The asset import worker launched by the editor.
+    AssetImportWorker,
+    /// UnityShaderCompiler launched by the editor.
+    ShaderCompiler,
+    /// The Unity Package Manager server launched by the editor.
+    PackageManager,
+    /// Unity Licensing Client.
+    LicensingClient,
+    /// UnityCrashHandler or Unity Bug Reporter.
+    CrashHandler,
+    /// The standalone player built with Unity.
+    Player,
+}
+
+impl UnityProcessKind {
+    /// Classifies the process with the executable, command line,
+    /// and whether the parent process is the editor.
+    pub(crate) fn classify(
+        exe: &Path,
+        cmd: &[OsString],
+        parent_is_editor: bool,
+    ) -> Option<UnityProcessKind> {
+        if is_editor_exe(exe) {
+            let has_flag = |flag: &str| cmd.iter().any(|x| x.eq_ignore_ascii_case(flag));
+            let worker_log = cmd
+                .iter()
+                .any(|x| x.to_string_lossy().contains("AssetImportWorker"));
+            if has_flag("-srvPort") || has_flag("-importWorker") || worker_log {
+                return Some(UnityProcessKind::AssetImportWorker);
+            }
+            if parent_is_editor && has_flag("-batchMode") {
+                // the editor launched by another editor in batchmode is a worker
+                return Some(UnityProcessKind::AssetImportWorker);
+            }
+            return Some(UnityProcessKind::Editor);
+        }
+
+        let name = exe.file_name()?.to_str()?.to_ascii_lowercase();
+        match name.strip_suffix(".exe").unwrap_or(&name) {
+            "unityshadercompiler" => Some(UnityProcessKind::ShaderCompiler),
+            "unitypackagemanager" => Some(UnityProcessKind::PackageManager),
+            "unity.licensing.client" => Some(UnityProcessKind::LicensingClient),
+            "unitycrashhandler32"
+            | "unitycrashhandler64"
+            | "unity bug reporter"
+            | "unitybugreporter" => Some(UnityProcessKind::CrashHandler),
+            _ => player_data_dir(exe).map(|_| UnityProcessKind::Player),
+        }
+    }
+}
+
+pub(crate) fn is_editor_exe(exe: &Path) -> bool {
+    #[cfg(target_os = "macos")]
+    let exe_name: &Path = "Contents/MacOS/Unity".as_ref();
+    #[cfg(target_os = "windows")]
+    let exe_name: &Path = "Unity.exe".as_ref();
+    #[cfg(target_os = "linux")]
+    let exe_name: &Path = "Unity".as_ref();
+
+    exe.ends_with(exe_name)
+}
+
+/// Finds the data folder of the player, like `Game_Data` next to `Game.exe`
+/// or `Game.app/Contents/Resources/Data`.
+pub(crate) fn player_data_dir(exe: &Path) -> Option<PathBuf> {
+    let dir = exe.parent()?;
+    let data = if cfg!(target_os = "macos") {
+        dir.parent()?.join("Resources/Data")
+    } else {
+        dir.join(format!("{}_Data", exe.file_stem()?.to_str()?))
+    };
+    // the player has globalgamemanagers, or data.unity3d if the build is compressed
+    let is_player =
+        data.join("globalgamemanagers").is_file() || data.join("data.unity3d").is_file();
+    is_player.then_some(data)
+}
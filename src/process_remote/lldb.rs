@@ -3,8 +3,8 @@ mod unix;
 
 use super::ProcessRemoteError;
 use lldb::{
-    lldb_addr_t, lldb_pid_t, ByteOrder, SBAttachInfo, SBDebugger, SBError, SBExpressionOptions,
-    SBFrame,
+    lldb_addr_t, lldb_pid_t, ByteOrder, Permissions, SBAttachInfo, SBDebugger, SBError,
+    SBExpressionOptions, SBFrame,
 };
 use std::io::Write;
 
@@ -18,7 +18,9 @@ use common::prepare_debug_server;
 #[cfg(unix)]
 use unix::prepare_debug_server;
 
-pub fn get_buffer(pid: lldb_pid_t) -> Result<Vec<u8>, ProcessRemoteError> {
+/// Gets the data from the process.
+/// The options are copied to the process and passed to `CONSOLE_LOG_SAVER_SAVE`.
+pub fn get_buffer(pid: lldb_pid_t, options: &[u8]) -> Result<Vec<u8>, ProcessRemoteError> {
     SBDebugger::initialize();
 
     let _debugserver = prepare_debug_server()?;
@@ -101,20 +103,33 @@ pub fn get_buffer(pid: lldb_pid_t) -> Result<Vec<u8>, ProcessRemoteError> {
     let free_mem = load_image.free_mem();
     let location = load_image.location();
 
+    let options_location = process
+        .allocate_memory(
+            options.len().max(1),
+            Permissions::READABLE | Permissions::WRITABLE,
+        )
+        .expect("allocating options");
+    process
+        .write_memory(options_location, options)
+        .expect("writing options");
+
     eval_expr(
         &frame,
         &format!(
             r##"
         #!mini-llvm-expr 1
         const target_ptr ptr {saver_save}
-        define_function_type void void_no_arg
-        call _ void_no_arg target_ptr
+        const options_ptr ptr {options_location}
+        define_function_type void void_ptr ptr
+        call _ void_ptr target_ptr options_ptr
         ret_void
         "##
         ),
     )
     .expect("calling saver");
 
+    unsafe { process.deallocate_memory(options_location) }.expect("deallocating options");
+
     let mut pointer = 0usize;
     process
         .read_memory(
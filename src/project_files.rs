@@ -0,0 +1,127 @@
+use crate::cls_file::single_line;
+use crate::{ReplaceSet, UpmDependencyType};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The files in `Assets`, embedded, local and cached packages of the project.
+pub(crate) struct ProjectFiles {
+    project: PathBuf,
+    roots: Vec<PathBuf>,
+}
+
+impl ProjectFiles {
+    pub fn new(project: &Path) -> Self {
+        let mut roots = Vec::new();
+        for root in scan_roots(project) {
+            let Ok(root) = std::fs::canonicalize(&root) else {
+                continue;
+            };
+            if !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+        ProjectFiles {
+            project: std::fs::canonicalize(project).unwrap_or_else(|_| project.to_owned()),
+            roots,
+        }
+    }
+
+    /// Calls `visit` with the files and folders and whether it's a folder.
+    /// The folder is walked into if `visit` returns true.
+    pub fn walk(&self, mut visit: impl FnMut(PathBuf, bool) -> bool) {
+        for root in &self.roots {
+            walk_dir(root, &mut visit);
+        }
+    }
+
+    /// The path relative to the project if it's in the project, with personal information hidden.
+    pub fn display_path(&self, path: &Path, replacer: &ReplaceSet) -> String {
+        let path = match path.strip_prefix(&self.project) {
+            Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+            Err(_) => path.to_string_lossy().into_owned(),
+        };
+        let path = replacer.replace_all(Cow::Owned(path));
+        single_line(&path).into_owned()
+    }
+}
+
+fn scan_roots(project: &Path) -> Vec<PathBuf> {
+    #[derive(Deserialize)]
+    struct PackageLock {
+        dependencies: BTreeMap<String, LockedDependency>,
+    }
+    #[derive(Deserialize)]
+    struct LockedDependency {
+        version: Option<String>,
+    }
+
+    let packages_dir = project.join("Packages");
+    let mut roots = vec![project.join("Assets")];
+
+    if let Ok(entries) = std::fs::read_dir(&packages_dir) {
+        roots.extend(entries.filter_map(|x| x.ok()).map(|x| x.path()));
+    }
+
+    let lock = read_json::<PackageLock>(&packages_dir.join("packages-lock.json"));
+    for version in lock.into_iter().flat_map(|x| x.dependencies.into_values()) {
+        let Some(version) = version.version else {
+            continue;
+        };
+        if let UpmDependencyType::FileRelative | UpmDependencyType::FileAbsolute =
+            UpmDependencyType::detect_from_version(&version)
+        {
+            let path = version.strip_prefix("file:").unwrap_or(&version);
+            roots.push(packages_dir.join(path));
+        }
+    }
+
+    roots.push(project.join("Library/PackageCache"));
+    roots
+}
+
+fn walk_dir(dir: &Path, visit: &mut impl FnMut(PathBuf, bool) -> bool) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|x| x.ok()) {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        // unity ignores hidden folders and folders ends with `~`
+        if name.starts_with('.') || name.ends_with('~') {
+            continue;
+        }
+        let path = entry.path();
+        if file_type.is_dir() {
+            if visit(path.clone(), true) {
+                walk_dir(&path, visit);
+            }
+        } else {
+            visit(path, false);
+        }
+    }
+}
+
+/// Reads the json file. Files like asmdef are often saved with BOM.
+pub(crate) fn read_json<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let json = std::fs::read(path).ok()?;
+    let json = json.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&json);
+    serde_json::from_slice(json).ok()
+}
+
+/// The lower case extension of the path.
+pub(crate) fn extension(path: &Path) -> Option<String> {
+    Some(path.extension()?.to_string_lossy().to_ascii_lowercase())
+}
+
+/// Reads the `.meta` file of the asset.
+pub(crate) fn read_meta(path: &Path) -> Option<String> {
+    let mut meta = path.as_os_str().to_owned();
+    meta.push(".meta");
+    std::fs::read_to_string(meta).ok()
+}
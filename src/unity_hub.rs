@@ -0,0 +1,210 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// The project listed in Unity Hub.
+#[derive(Debug, Clone)]
+pub struct UnityHubProject {
+    path: PathBuf,
+    title: Option<String>,
+    editor_version: Option<String>,
+    last_modified: Option<SystemTime>,
+}
+
+impl UnityHubProject {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The name shown in Unity Hub, or the name of the project folder.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref().or(self.path.file_name()?.to_str())
+    }
+
+    /// The editor version of the project in Unity Hub, or in `ProjectVersion.txt` of the project.
+    pub fn editor_version(&self) -> Option<&str> {
+        self.editor_version.as_deref()
+    }
+
+    /// The time the project was last opened or modified.
+    pub fn last_modified(&self) -> Option<SystemTime> {
+        self.last_modified
+    }
+}
+
+/// The editor installed with or located in Unity Hub.
+#[derive(Debug, Clone)]
+pub struct UnityHubEditor {
+    version: String,
+    exe_path: PathBuf,
+}
+
+impl UnityHubEditor {
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// The path of the editor executable.
+    pub fn exe_path(&self) -> &Path {
+        &self.exe_path
+    }
+}
+
+/// The directory Unity Hub saves its configuration to.
+fn unity_hub_config_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let dir = PathBuf::from(std::env::var_os("APPDATA")?).join("UnityHub");
+    #[cfg(target_os = "macos")]
+    let dir = home::home_dir()?.join("Library/Application Support/UnityHub");
+    #[cfg(target_os = "linux")]
+    let dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| Some(home::home_dir()?.join(".config")))?
+        .join("UnityHub");
+    Some(dir)
+}
+
+/// Reads the projects listed in Unity Hub, most recently used first.
+pub fn unity_hub_projects() -> Vec<UnityHubProject> {
+    #[derive(Deserialize)]
+    struct ProjectsV1 {
+        data: BTreeMap<String, ProjectV1>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ProjectV1 {
+        path: Option<PathBuf>,
+        title: Option<String>,
+        version: Option<String>,
+        last_modified: Option<u64>,
+    }
+
+    let Some(config_dir) = unity_hub_config_dir() else {
+        return vec![];
+    };
+    let Ok(json) = std::fs::read_to_string(config_dir.join("projects-v1.json")) else {
+        return vec![];
+    };
+    let Ok(projects) = serde_json::from_str::<ProjectsV1>(&json) else {
+        return vec![];
+    };
+
+    let mut projects = projects
+        .data
+        .into_iter()
+        .map(|(key, project)| {
+            let path = project.path.unwrap_or_else(|| PathBuf::from(key));
+            let editor_version = project
+                .version
+                .filter(|x| !x.is_empty())
+                .or_else(|| crate::read_project_version(&path));
+            UnityHubProject {
+                path,
+                title: project.title.filter(|x| !x.is_empty()),
+                editor_version,
+                // lastModified is milliseconds since epoch
+                last_modified: project
+                    .last_modified
+                    .map(|x| SystemTime::UNIX_EPOCH + Duration::from_millis(x)),
+            }
+        })
+        .collect::<Vec<_>>();
+    projects.sort_by_key(|x| std::cmp::Reverse(x.last_modified));
+    projects
+}
+
+/// Reads the paths of the projects listed in Unity Hub.
+pub(crate) fn unity_hub_project_paths() -> Vec<PathBuf> {
+    unity_hub_projects().into_iter().map(|x| x.path).collect()
+}
+
+/// Finds the editors located in `editors.json` and installed to the install locations of Unity Hub.
+pub fn unity_hub_editors() -> Vec<UnityHubEditor> {
+    #[derive(Deserialize)]
+    struct Editor {
+        location: Option<Location>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Location {
+        One(PathBuf),
+        Many(Vec<PathBuf>),
+    }
+
+    let mut editors = Vec::new();
+
+    let config_dir = unity_hub_config_dir();
+
+    // editors located manually
+    if let Some(config_dir) = &config_dir {
+        if let Ok(json) = std::fs::read_to_string(config_dir.join("editors.json")) {
+            if let Ok(located) = serde_json::from_str::<BTreeMap<String, Editor>>(&json) {
+                for (version, editor) in located {
+                    let exe_path = match editor.location {
+                        Some(Location::One(path)) => path,
+                        Some(Location::Many(paths)) => {
+                            let Some(path) = paths.into_iter().next() else {
+                                continue;
+                            };
+                            path
+                        }
+                        None => continue,
+                    };
+                    editors.push(UnityHubEditor { version, exe_path });
+                }
+            }
+        }
+    }
+
+    // editors installed with Unity Hub
+    let mut install_dirs = Vec::new();
+    if let Some(config_dir) = &config_dir {
+        // the file is a json string
+        if let Ok(json) = std::fs::read_to_string(config_dir.join("secondaryInstallPath.json")) {
+            if let Ok(path) = serde_json::from_str::<PathBuf>(&json) {
+                if !path.as_os_str().is_empty() {
+                    install_dirs.push(path);
+                }
+            }
+        }
+    }
+    install_dirs.extend(default_install_dir());
+
+    for install_dir in install_dirs {
+        let Ok(entries) = std::fs::read_dir(&install_dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(|x| x.ok()) {
+            let Ok(version) = entry.file_name().into_string() else {
+                continue;
+            };
+            let exe_path = entry.path().join(EDITOR_EXE_IN_INSTALL_DIR);
+            if !exe_path.exists() || editors.iter().any(|x| x.version == version) {
+                continue;
+            }
+            editors.push(UnityHubEditor { version, exe_path });
+        }
+    }
+
+    editors
+}
+
+#[cfg(target_os = "windows")]
+const EDITOR_EXE_IN_INSTALL_DIR: &str = "Editor/Unity.exe";
+#[cfg(target_os = "macos")]
+const EDITOR_EXE_IN_INSTALL_DIR: &str = "Unity.app/Contents/MacOS/Unity";
+#[cfg(target_os = "linux")]
+const EDITOR_EXE_IN_INSTALL_DIR: &str = "Editor/Unity";
+
+fn default_install_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let dir = PathBuf::from(std::env::var_os("ProgramFiles")?).join("Unity/Hub/Editor");
+    #[cfg(target_os = "macos")]
+    let dir = PathBuf::from("/Applications/Unity/Hub/Editor");
+    #[cfg(target_os = "linux")]
+    let dir = home::home_dir()?.join("Unity/Hub/Editor");
+    Some(dir)
+}
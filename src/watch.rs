@@ -0,0 +1,105 @@
+use crate::process_remote::base_err;
+use crate::{
+    find_unity_processes_in, run_console_log_saver, CaptureCursor, ClsFile, ConsoleLogSaverConfig,
+    LogSeverity, ProcessId, Result, UnityProcess,
+};
+use std::ops::ControlFlow;
+use std::path::Path;
+use std::time::Duration;
+
+/// The capture with new entries made by [`watch_console_log_saver`].
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct WatchCapture {
+    /// The process the log is captured from.
+    pub pid: ProcessId,
+    /// The CLS file with the entries logged after the previous capture.
+    pub log: String,
+    /// The number of the log elements in `log`.
+    pub new_entries: usize,
+    /// The number of the error log elements in `log`.
+    pub new_errors: usize,
+    /// True if the console was cleared or the editor was restarted after the previous capture.
+    pub console_cleared: bool,
+}
+
+/// Captures the log of the project periodically and calls `on_capture` with the new entries.
+///
+/// If the editor is closed, this waits for the editor for the same project to be opened again.
+/// Failures of capturing are passed to `on_capture` and the loop continues.
+/// The loop ends when `on_capture` returns [`ControlFlow::Break`].
+pub fn watch_console_log_saver(
+    process: &UnityProcess,
+    config: &ConsoleLogSaverConfig,
+    interval: Duration,
+    mut on_capture: impl FnMut(Result<WatchCapture>) -> ControlFlow<()>,
+) {
+    let mut config = config.clone();
+    let mut pid = Some(process.pid());
+
+    loop {
+        if let Some(current) = find_process(pid, process.project_path()) {
+            pid = Some(current);
+            let result = capture(current, &mut config);
+            let flow = match result {
+                Ok(None) => ControlFlow::Continue(()),
+                Ok(Some(capture)) => on_capture(Ok(capture)),
+                Err(err) => on_capture(Err(err)),
+            };
+            if flow.is_break() {
+                return;
+            }
+        } else {
+            pid = None;
+        }
+
+        std::thread::sleep(interval);
+    }
+}
+
+/// Finds the process with the pid, or the editor opened for the project
+fn find_process(pid: Option<ProcessId>, project_path: &Path) -> Option<ProcessId> {
+    // the project itself is searched for the editor opened without -projectPath
+    let processes = find_unity_processes_in(&[project_path.to_owned()]);
+    processes
+        .iter()
+        .find(|x| Some(x.pid()) == pid)
+        .or_else(|| {
+            let project_path = project_path.to_string_lossy();
+            processes.iter().find(|x| x.is_for_project(&project_path))
+        })
+        .map(|x| x.pid())
+}
+
+/// Captures entries after `config.since` and updates `config.since`.
+/// Returns None if there are no new entries.
+fn capture(pid: ProcessId, config: &mut ConsoleLogSaverConfig) -> Result<Option<WatchCapture>> {
+    let log = run_console_log_saver(pid, config)?;
+    let cls_file =
+        ClsFile::parse(&log).map_err(|err| base_err(format!("invalid generated log: {err}")))?;
+
+    let console_cleared = cls_file.header("Console-Cleared").is_some();
+    let log_elements = cls_file
+        .sections()
+        .iter()
+        .filter(|x| x.content_type() == Some("log-element"));
+    let new_entries = log_elements.clone().count();
+    let new_errors = log_elements
+        .filter_map(|x| x.field("Mode")?.parse::<i32>().ok())
+        .filter(|&mode| LogSeverity::from_mode(mode) == LogSeverity::Error)
+        .count();
+
+    config.since = CaptureCursor::from_cls_file(&cls_file);
+
+    if new_entries == 0 && !console_cleared {
+        return Ok(None);
+    }
+
+    Ok(Some(WatchCapture {
+        pid,
+        log,
+        new_entries,
+        new_errors,
+        console_cleared,
+    }))
+}
@@ -29,13 +29,25 @@
     const logList = document.querySelector(".page-logs-list");
 
     const input = document.querySelector('.page-input-text-area');
-    document.querySelector(".page-input-parse-button").addEventListener('click', (e) => {
+    document.querySelector(".page-input-parse-button").addEventListener('click', async (e) => {
         e.stopPropagation();
 
+        let text = input.value;
+        if (isEncryptedContent(text)) {
+            const passphrase = prompt("This log is encrypted. Please enter the passphrase.");
+            if (passphrase == null) return;
+            try {
+                text = await decryptContent(text, passphrase);
+            } catch (e) {
+                alert(`Error decrypting log: ${e?.message}`)
+                return;
+            }
+        }
+
         const children = [];
         let parsed;
         try {
-            parsed = parseContent(input.value);
+            parsed = parseContent(text);
         } catch (e) {
             alert(`Error parsing log: ${e?.message}`)
             return;
@@ -45,25 +57,29 @@
         const elementByText = {};
 
         for (const section of parsed.sections) {
-            if (section.contentType !== "log-element") continue;
+            // editor-log and player-log are the whole log file, shown as one element
+            if (!["log-element", "editor-log", "player-log", "compiler-message"].includes(section.contentType)) continue;
 
             // sections
             // TODO: log type detection
             const cloned = template.content.cloneNode(true).querySelector(".page-logs-element");
-            cloned.dataset.logLevel = getLogLevel(parseInt(section.getField('Mode-Raw'), 16));
+            // compiler-message has Severity instead of Mode-Raw
+            cloned.dataset.logLevel = section.getField('Severity') ?? getLogLevel(parseInt(section.getField('Mode-Raw'), 16));
             const lines = section.content.split(/\r?\n/g);
             cloned.querySelector(".page-logs-element-text-short").textContent = lines.length === 1 ? lines[0] : `${lines[0]}\n${lines[1]}`;
             cloned.dataset.fullText = section.content;
             // collapse
+            // deduplicated entries have Repeat-Count
+            const repeatCount = parseInt(section.getField('Repeat-Count') ?? '1');
             let originForText = elementByText[section.content]
             if (originForText != null) {
                 cloned.dataset.collapsed = 'true';
                 const collasedCount = originForText.querySelector(".page-logs-element-collapsed-count");
-                collasedCount.textContent = parseInt(collasedCount.textContent) + 1;
+                collasedCount.textContent = parseInt(collasedCount.textContent) + repeatCount;
             } else {
                 originForText = elementByText[section.content] = cloned;
                 const collasedCount = originForText.querySelector(".page-logs-element-collapsed-count");
-                collasedCount.textContent = 1;
+                collasedCount.textContent = repeatCount;
             }
             children.push(cloned);
         }
@@ -215,6 +231,55 @@
     }
 })();
 
+/**
+ * @param content {string}
+ * @return {boolean}
+ */
+function isEncryptedContent(content) {
+    return content.startsWith('ConsoleLogSaverEncrypted/1.');
+}
+
+/**
+ * Decrypts the log encrypted with AES-256-GCM with key derived by PBKDF2-HMAC-SHA256
+ * @param content {string}
+ * @param passphrase {string}
+ * @return {Promise<string>}
+ */
+async function decryptContent(content, passphrase) {
+    const lines = content.split(/\r\n|\n/);
+    const fieldsEnd = lines.indexOf("");
+    if (fieldsEnd === -1) throw new Error(`corrupted encrypted log`);
+    const fields = {};
+    for (const line of lines.slice(1, fieldsEnd)) {
+        const colon = line.indexOf(':');
+        if (colon === -1) throw new Error(`corrupted encrypted log`);
+        fields[line.substring(0, colon).toLowerCase()] = line.substring(colon + 1).trim();
+    }
+    if (fields['encryption'] !== 'AES-256-GCM' || fields['key-derivation'] !== 'PBKDF2-HMAC-SHA256')
+        throw new Error(`unsupported encryption`);
+
+    const fromBase64 = (s) => Uint8Array.from(atob(s), c => c.charCodeAt(0));
+    const salt = fromBase64(fields['salt']);
+    const iv = fromBase64(fields['nonce']);
+    const iterations = parseInt(fields['iterations']);
+    // a crafted file with huge iterations would freeze the page
+    if (!(iterations > 0 && iterations <= 10_000_000)) throw new Error(`unsupported encryption`);
+    const body = fromBase64(lines.slice(fieldsEnd + 1).join('').trim());
+
+    const baseKey = await crypto.subtle.importKey(
+        'raw', new TextEncoder().encode(passphrase), 'PBKDF2', false, ['deriveKey']);
+    const key = await crypto.subtle.deriveKey(
+        {name: 'PBKDF2', salt, iterations, hash: 'SHA-256'},
+        baseKey, {name: 'AES-GCM', length: 256}, false, ['decrypt']);
+    let decrypted;
+    try {
+        decrypted = await crypto.subtle.decrypt({name: 'AES-GCM', iv}, key, body);
+    } catch (e) {
+        throw new Error(`wrong passphrase or corrupted log`);
+    }
+    return new TextDecoder().decode(decrypted);
+}
+
 /**
  *
  * @param content {string}